@@ -1,4 +1,62 @@
+use std::error::Error;
 use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// The error returned when parsing a color string fails.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::ansi::{Ansi, ParseColorError};
+///
+/// assert_eq!(Ansi::from_hex("#12345"), Err(ParseColorError::InvalidLength(5)));
+/// assert_eq!(Ansi::from_hex("#zz0000"), Err(ParseColorError::InvalidDigit('z')));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseColorError {
+    /// The number of hex digits is neither 3 nor 6.
+    InvalidLength(usize),
+    /// The string contains a character that is not a hex digit.
+    InvalidDigit(char),
+}
+
+impl Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseColorError::InvalidLength(len) => {
+                write!(
+                    f,
+                    "invalid hex color length {}, expected 3 or 6 digits",
+                    len
+                )
+            }
+            ParseColorError::InvalidDigit(c) => write!(f, "invalid hex digit '{}'", c),
+        }
+    }
+}
+
+impl Error for ParseColorError {}
+
+/// The error returned when parsing an unknown ANSI name.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::ansi::{Ansi, ParseAnsiError};
+///
+/// let err = "purple".parse::<Ansi>().unwrap_err();
+/// assert_eq!(err, ParseAnsiError("purple".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseAnsiError(pub String);
+
+impl Display for ParseAnsiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown ANSI name '{}'", self.0)
+    }
+}
+
+impl Error for ParseAnsiError {}
 
 /// Represents a terminal color attribute using ANSI escape codes.
 ///
@@ -12,67 +70,889 @@ use std::fmt::{self, Display};
 ///
 /// let bg_red = Ansi::BgRed;
 /// assert_eq!(format!("{}", bg_red), "\x1b[41m");
+///
+/// let fg_orange = Ansi::FgRgb(255, 128, 0);
+/// assert_eq!(format!("{}", fg_orange), "\x1b[38;2;255;128;0m");
+///
+/// // RGB tuples convert into foreground colors.
+/// assert_eq!(Ansi::from((255, 128, 0)), fg_orange);
 /// ```
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Ansi {
     // Control,
-    Reset = 0,
-    Bold = 1,
-    Faint = 2,
-    Italic = 3,
-    Underline = 4,
-    BlinkSlow = 5,
-    BlinkRapid = 6,
-    ReverseVideo = 7,
-    Concealed = 8,
-    CrossedOut = 9,
+    Reset,
+    Bold,
+    Faint,
+    Italic,
+    Underline,
+    BlinkSlow,
+    BlinkRapid,
+    ReverseVideo,
+    Concealed,
+    CrossedOut,
+    DoubleUnderline,
+    Overline,
+    // Off
+    BoldOff,
+    FaintOff,
+    ItalicOff,
+    UnderlineOff,
+    BlinkOff,
+    ReverseOff,
+    ConcealedOff,
+    CrossedOutOff,
     // FG
-    FgBlack = 30,
-    FgRed = 31,
-    FgGreen = 32,
-    FgYellow = 33,
-    FgBlue = 34,
-    FgMagenta = 35,
-    FgCyan = 36,
-    FgWhite = 37,
-    FgHiBlack = 90,
-    FgHiRed = 91,
-    FgHiGreen = 92,
-    FgHiYellow = 93,
-    FgHiBlue = 94,
-    FgHiMagenta = 95,
-    FgHiCyan = 96,
-    FgHiWhite = 97,
+    FgBlack,
+    FgRed,
+    FgGreen,
+    FgYellow,
+    FgBlue,
+    FgMagenta,
+    FgCyan,
+    FgWhite,
+    FgHiBlack,
+    FgHiRed,
+    FgHiGreen,
+    FgHiYellow,
+    FgHiBlue,
+    FgHiMagenta,
+    FgHiCyan,
+    FgHiWhite,
+    FgDefault,
     // BG
-    BgBlack = 40,
-    BgRed = 41,
-    BgGreen = 42,
-    BgYellow = 43,
-    BgBlue = 44,
-    BgMagenta = 45,
-    BgCyan = 46,
-    BgWhite = 47,
-    BgHiBlack = 100,
-    BgHiRed = 101,
-    BgHiGreen = 102,
-    BgHiYellow = 103,
-    BgHiBlue = 104,
-    BgHiMagenta = 105,
-    BgHiCyan = 106,
-    BgHiWhite = 107,
+    BgBlack,
+    BgRed,
+    BgGreen,
+    BgYellow,
+    BgBlue,
+    BgMagenta,
+    BgCyan,
+    BgWhite,
+    BgHiBlack,
+    BgHiRed,
+    BgHiGreen,
+    BgHiYellow,
+    BgHiBlue,
+    BgHiMagenta,
+    BgHiCyan,
+    BgHiWhite,
+    BgDefault,
+    // 256
+    Fg256(u8),
+    Bg256(u8),
+    // RGB
+    FgRgb(u8, u8, u8),
+    BgRgb(u8, u8, u8),
+    // Underline color
+    UnderlineColor256(u8),
+    UnderlineColorRgb(u8, u8, u8),
+    UnderlineColorDefault,
+}
+
+impl Ansi {
+    /// Parses a hex color string into a foreground RGB code.
+    /// Accepts `#rrggbb`, `rrggbb` and the shorthand `#rgb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::from_hex("#ff8800"), Ok(Ansi::FgRgb(255, 136, 0)));
+    /// assert_eq!(Ansi::from_hex("f80"), Ok(Ansi::FgRgb(255, 136, 0)));
+    /// assert!(Ansi::from_hex("#ff88").is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Ansi, ParseColorError> {
+        let (r, g, b) = parse_hex(hex)?;
+        Ok(Ansi::FgRgb(r, g, b))
+    }
+
+    /// Parses a hex color string into a background RGB code.
+    /// Accepts `#rrggbb`, `rrggbb` and the shorthand `#rgb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::from_hex_bg("#ff8800"), Ok(Ansi::BgRgb(255, 136, 0)));
+    /// ```
+    pub fn from_hex_bg(hex: &str) -> Result<Ansi, ParseColorError> {
+        let (r, g, b) = parse_hex(hex)?;
+        Ok(Ansi::BgRgb(r, g, b))
+    }
+
+    /// Creates a foreground RGB code from a HSL color.
+    /// The hue is given in degrees from 0 to 360, saturation and lightness
+    /// from 0.0 to 1.0. Out of range values are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::from_hsl(0.0, 1.0, 0.5), Ansi::FgRgb(255, 0, 0));
+    /// ```
+    pub fn from_hsl(h: f64, s: f64, l: f64) -> Ansi {
+        let (r, g, b) = hsl_to_rgb(h, s, l);
+        Ansi::FgRgb(r, g, b)
+    }
+
+    /// Creates a background RGB code from a HSL color.
+    /// The hue is given in degrees from 0 to 360, saturation and lightness
+    /// from 0.0 to 1.0. Out of range values are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::from_hsl_bg(240.0, 1.0, 0.5), Ansi::BgRgb(0, 0, 255));
+    /// ```
+    pub fn from_hsl_bg(h: f64, s: f64, l: f64) -> Ansi {
+        let (r, g, b) = hsl_to_rgb(h, s, l);
+        Ansi::BgRgb(r, g, b)
+    }
+
+    /// Creates a foreground code from the grayscale ramp of the 256 color palette.
+    /// The level goes from 0 (dark) to 23 (light), higher values are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::gray(0), Ansi::Fg256(232));
+    /// assert_eq!(Ansi::gray(23), Ansi::Fg256(255));
+    /// ```
+    pub fn gray(level: u8) -> Ansi {
+        Ansi::Fg256(232 + level.min(23))
+    }
+
+    /// Creates a background code from the grayscale ramp of the 256 color palette.
+    /// The level goes from 0 (dark) to 23 (light), higher values are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::gray_bg(0), Ansi::Bg256(232));
+    /// ```
+    pub fn gray_bg(level: u8) -> Ansi {
+        Ansi::Bg256(232 + level.min(23))
+    }
+
+    /// Returns a random basic foreground color using the thread RNG.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let color = Ansi::random_fg();
+    /// assert!(color.is_foreground());
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn random_fg() -> Ansi {
+        use rand::Rng;
+        BASIC16[rand::thread_rng().gen_range(0..BASIC16.len())].0
+    }
+
+    /// Returns a random basic background color using the thread RNG.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let color = Ansi::random_bg();
+    /// assert!(color.is_background());
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn random_bg() -> Ansi {
+        use rand::Rng;
+        BASIC16[rand::thread_rng().gen_range(0..BASIC16.len())].1
+    }
+
+    /// Creates a foreground code from the 6x6x6 color cube of the 256 color palette.
+    /// Each component goes from 0 to 5, higher values are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::cube(5, 0, 0), Ansi::Fg256(196));
+    /// ```
+    pub fn cube(r: u8, g: u8, b: u8) -> Ansi {
+        Ansi::Fg256(16 + 36 * r.min(5) + 6 * g.min(5) + b.min(5))
+    }
+
+    // Returns the SGR parameters of the variant, e.g. `38;2;255;0;0`.
+    pub(crate) fn params(&self) -> String {
+        match self {
+            Ansi::Fg256(n) | Ansi::Bg256(n) | Ansi::UnderlineColor256(n) => {
+                format!("{};5;{}", self.code(), n)
+            }
+            Ansi::FgRgb(r, g, b) | Ansi::BgRgb(r, g, b) | Ansi::UnderlineColorRgb(r, g, b) => {
+                format!("{};2;{};{};{}", self.code(), r, g, b)
+            }
+            _ => self.code().to_string(),
+        }
+    }
+
+    /// Converts a RGB or 256 color to the closest of the basic 16 colors.
+    /// The distance is measured as Euclidean distance in RGB space.
+    /// Other values are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::FgRgb(250, 10, 10).to_basic(), Ansi::FgRed);
+    /// assert_eq!(Ansi::BgRgb(0, 0, 180).to_basic(), Ansi::BgBlue);
+    /// assert_eq!(Ansi::Fg256(196).to_basic(), Ansi::FgRed);
+    /// assert_eq!(Ansi::Bold.to_basic(), Ansi::Bold);
+    /// ```
+    pub fn to_basic(&self) -> Ansi {
+        match *self {
+            Ansi::Fg256(n) if n < 16 => BASIC16[n as usize].0,
+            Ansi::Bg256(n) if n < 16 => BASIC16[n as usize].1,
+            Ansi::Fg256(n) => Ansi::from_256_rgb(n, true).to_basic(),
+            Ansi::Bg256(n) => Ansi::from_256_rgb(n, false).to_basic(),
+            Ansi::FgRgb(r, g, b) => BASIC16[nearest_basic16(r, g, b)].0,
+            Ansi::BgRgb(r, g, b) => BASIC16[nearest_basic16(r, g, b)].1,
+            ansi => ansi,
+        }
+    }
+
+    /// Converts a RGB color to the closest color of the 256 color palette.
+    /// Both the 6x6x6 color cube and the grayscale ramp are considered.
+    /// Other values are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::FgRgb(255, 0, 0).to_256(), Ansi::Fg256(196));
+    /// assert_eq!(Ansi::BgRgb(128, 128, 128).to_256(), Ansi::Bg256(244));
+    /// ```
+    pub fn to_256(&self) -> Ansi {
+        match *self {
+            Ansi::FgRgb(r, g, b) => Ansi::Fg256(nearest_256(r, g, b)),
+            Ansi::BgRgb(r, g, b) => Ansi::Bg256(nearest_256(r, g, b)),
+            Ansi::UnderlineColorRgb(r, g, b) => Ansi::UnderlineColor256(nearest_256(r, g, b)),
+            ansi => ansi,
+        }
+    }
+
+    // Returns the RGB variant of a 256 palette index.
+    fn from_256_rgb(n: u8, fg: bool) -> Ansi {
+        let (r, g, b) = palette_256_rgb(n);
+        if fg {
+            Ansi::FgRgb(r, g, b)
+        } else {
+            Ansi::BgRgb(r, g, b)
+        }
+    }
+
+    // Returns the RGB value of a foreground or background color.
+    pub(crate) fn rgb(&self) -> Option<(u8, u8, u8)> {
+        match *self {
+            Ansi::Fg256(n) | Ansi::Bg256(n) => Some(palette_256_rgb(n)),
+            Ansi::FgRgb(r, g, b) | Ansi::BgRgb(r, g, b) => Some((r, g, b)),
+            ansi => BASIC16
+                .iter()
+                .find(|(fg, bg, _)| *fg == ansi || *bg == ansi)
+                .map(|(_, _, rgb)| *rgb),
+        }
+    }
+
+    /// Returns whether the ANSI value sets the foreground color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert!(Ansi::FgRed.is_foreground());
+    /// assert!(Ansi::FgRgb(255, 0, 0).is_foreground());
+    /// assert!(!Ansi::BgRed.is_foreground());
+    /// ```
+    pub fn is_foreground(&self) -> bool {
+        matches!(
+            self,
+            Ansi::FgBlack
+                | Ansi::FgRed
+                | Ansi::FgGreen
+                | Ansi::FgYellow
+                | Ansi::FgBlue
+                | Ansi::FgMagenta
+                | Ansi::FgCyan
+                | Ansi::FgWhite
+                | Ansi::FgHiBlack
+                | Ansi::FgHiRed
+                | Ansi::FgHiGreen
+                | Ansi::FgHiYellow
+                | Ansi::FgHiBlue
+                | Ansi::FgHiMagenta
+                | Ansi::FgHiCyan
+                | Ansi::FgHiWhite
+                | Ansi::FgDefault
+                | Ansi::Fg256(_)
+                | Ansi::FgRgb(..)
+        )
+    }
+
+    /// Returns whether the ANSI value sets the background color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert!(Ansi::BgRed.is_background());
+    /// assert!(Ansi::Bg256(17).is_background());
+    /// assert!(!Ansi::FgRed.is_background());
+    /// ```
+    pub fn is_background(&self) -> bool {
+        matches!(
+            self,
+            Ansi::BgBlack
+                | Ansi::BgRed
+                | Ansi::BgGreen
+                | Ansi::BgYellow
+                | Ansi::BgBlue
+                | Ansi::BgMagenta
+                | Ansi::BgCyan
+                | Ansi::BgWhite
+                | Ansi::BgHiBlack
+                | Ansi::BgHiRed
+                | Ansi::BgHiGreen
+                | Ansi::BgHiYellow
+                | Ansi::BgHiBlue
+                | Ansi::BgHiMagenta
+                | Ansi::BgHiCyan
+                | Ansi::BgHiWhite
+                | Ansi::BgDefault
+                | Ansi::Bg256(_)
+                | Ansi::BgRgb(..)
+        )
+    }
+
+    /// Returns whether the ANSI value turns a text style on or off.
+    /// Underline colors count as style, the reset does not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert!(Ansi::Bold.is_style());
+    /// assert!(Ansi::ItalicOff.is_style());
+    /// assert!(!Ansi::Reset.is_style());
+    /// ```
+    pub fn is_style(&self) -> bool {
+        !self.is_reset() && !self.is_foreground() && !self.is_background()
+    }
+
+    /// Returns whether the ANSI value resets all styles and colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert!(Ansi::Reset.is_reset());
+    /// assert!(!Ansi::FgDefault.is_reset());
+    /// ```
+    pub fn is_reset(&self) -> bool {
+        *self == Ansi::Reset
+    }
+
+    /// Returns the numeric SGR code of the ANSI value.
+    /// Variants with multiple parameters return their leading selector,
+    /// e.g. `38` for `FgRgb` and `48` for `BgRgb`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// assert_eq!(Ansi::FgRed.code(), 31);
+    /// assert_eq!(Ansi::FgRgb(255, 0, 0).code(), 38);
+    /// ```
+    pub fn code(&self) -> u16 {
+        match self {
+            Ansi::Reset => 0,
+            Ansi::Bold => 1,
+            Ansi::Faint => 2,
+            Ansi::Italic => 3,
+            Ansi::Underline => 4,
+            Ansi::BlinkSlow => 5,
+            Ansi::BlinkRapid => 6,
+            Ansi::ReverseVideo => 7,
+            Ansi::Concealed => 8,
+            Ansi::CrossedOut => 9,
+            Ansi::DoubleUnderline => 21,
+            Ansi::Overline => 53,
+            Ansi::BoldOff => 22,
+            Ansi::FaintOff => 22,
+            Ansi::ItalicOff => 23,
+            Ansi::UnderlineOff => 24,
+            Ansi::BlinkOff => 25,
+            Ansi::ReverseOff => 27,
+            Ansi::ConcealedOff => 28,
+            Ansi::CrossedOutOff => 29,
+            Ansi::FgBlack => 30,
+            Ansi::FgRed => 31,
+            Ansi::FgGreen => 32,
+            Ansi::FgYellow => 33,
+            Ansi::FgBlue => 34,
+            Ansi::FgMagenta => 35,
+            Ansi::FgCyan => 36,
+            Ansi::FgWhite => 37,
+            Ansi::FgHiBlack => 90,
+            Ansi::FgHiRed => 91,
+            Ansi::FgHiGreen => 92,
+            Ansi::FgHiYellow => 93,
+            Ansi::FgHiBlue => 94,
+            Ansi::FgHiMagenta => 95,
+            Ansi::FgHiCyan => 96,
+            Ansi::FgHiWhite => 97,
+            Ansi::FgDefault => 39,
+            Ansi::BgBlack => 40,
+            Ansi::BgRed => 41,
+            Ansi::BgGreen => 42,
+            Ansi::BgYellow => 43,
+            Ansi::BgBlue => 44,
+            Ansi::BgMagenta => 45,
+            Ansi::BgCyan => 46,
+            Ansi::BgWhite => 47,
+            Ansi::BgHiBlack => 100,
+            Ansi::BgHiRed => 101,
+            Ansi::BgHiGreen => 102,
+            Ansi::BgHiYellow => 103,
+            Ansi::BgHiBlue => 104,
+            Ansi::BgHiMagenta => 105,
+            Ansi::BgHiCyan => 106,
+            Ansi::BgHiWhite => 107,
+            Ansi::BgDefault => 49,
+            Ansi::Fg256(_) | Ansi::FgRgb(..) => 38,
+            Ansi::Bg256(_) | Ansi::BgRgb(..) => 48,
+            Ansi::UnderlineColor256(_) | Ansi::UnderlineColorRgb(..) => 58,
+            Ansi::UnderlineColorDefault => 59,
+        }
+    }
+}
+
+// The basic 16 colors as foreground, background and their VGA RGB values.
+const BASIC16: [(Ansi, Ansi, (u8, u8, u8)); 16] = [
+    (Ansi::FgBlack, Ansi::BgBlack, (0, 0, 0)),
+    (Ansi::FgRed, Ansi::BgRed, (170, 0, 0)),
+    (Ansi::FgGreen, Ansi::BgGreen, (0, 170, 0)),
+    (Ansi::FgYellow, Ansi::BgYellow, (170, 85, 0)),
+    (Ansi::FgBlue, Ansi::BgBlue, (0, 0, 170)),
+    (Ansi::FgMagenta, Ansi::BgMagenta, (170, 0, 170)),
+    (Ansi::FgCyan, Ansi::BgCyan, (0, 170, 170)),
+    (Ansi::FgWhite, Ansi::BgWhite, (170, 170, 170)),
+    (Ansi::FgHiBlack, Ansi::BgHiBlack, (85, 85, 85)),
+    (Ansi::FgHiRed, Ansi::BgHiRed, (255, 85, 85)),
+    (Ansi::FgHiGreen, Ansi::BgHiGreen, (85, 255, 85)),
+    (Ansi::FgHiYellow, Ansi::BgHiYellow, (255, 255, 85)),
+    (Ansi::FgHiBlue, Ansi::BgHiBlue, (85, 85, 255)),
+    (Ansi::FgHiMagenta, Ansi::BgHiMagenta, (255, 85, 255)),
+    (Ansi::FgHiCyan, Ansi::BgHiCyan, (85, 255, 255)),
+    (Ansi::FgHiWhite, Ansi::BgHiWhite, (255, 255, 255)),
+];
+
+// All variants without parameters, used to look up codes.
+const SIMPLE: [Ansi; 55] = [
+    Ansi::Reset,
+    Ansi::Bold,
+    Ansi::Faint,
+    Ansi::Italic,
+    Ansi::Underline,
+    Ansi::BlinkSlow,
+    Ansi::BlinkRapid,
+    Ansi::ReverseVideo,
+    Ansi::Concealed,
+    Ansi::CrossedOut,
+    Ansi::DoubleUnderline,
+    Ansi::Overline,
+    Ansi::BoldOff,
+    Ansi::FaintOff,
+    Ansi::ItalicOff,
+    Ansi::UnderlineOff,
+    Ansi::BlinkOff,
+    Ansi::ReverseOff,
+    Ansi::ConcealedOff,
+    Ansi::CrossedOutOff,
+    Ansi::FgBlack,
+    Ansi::FgRed,
+    Ansi::FgGreen,
+    Ansi::FgYellow,
+    Ansi::FgBlue,
+    Ansi::FgMagenta,
+    Ansi::FgCyan,
+    Ansi::FgWhite,
+    Ansi::FgHiBlack,
+    Ansi::FgHiRed,
+    Ansi::FgHiGreen,
+    Ansi::FgHiYellow,
+    Ansi::FgHiBlue,
+    Ansi::FgHiMagenta,
+    Ansi::FgHiCyan,
+    Ansi::FgHiWhite,
+    Ansi::FgDefault,
+    Ansi::BgBlack,
+    Ansi::BgRed,
+    Ansi::BgGreen,
+    Ansi::BgYellow,
+    Ansi::BgBlue,
+    Ansi::BgMagenta,
+    Ansi::BgCyan,
+    Ansi::BgWhite,
+    Ansi::BgHiBlack,
+    Ansi::BgHiRed,
+    Ansi::BgHiGreen,
+    Ansi::BgHiYellow,
+    Ansi::BgHiBlue,
+    Ansi::BgHiMagenta,
+    Ansi::BgHiCyan,
+    Ansi::BgHiWhite,
+    Ansi::BgDefault,
+    Ansi::UnderlineColorDefault,
+];
+
+// Parses SGR parameters like `1;31` or `38;2;255;0;0` into ANSI values.
+// Unknown codes are skipped, an empty parameter list means reset.
+pub(crate) fn parse_params(params: &str) -> Vec<Ansi> {
+    if params.is_empty() {
+        return vec![Ansi::Reset];
+    }
+    let numbers: Vec<u16> = params
+        .split(';')
+        .map(|n| {
+            if n.is_empty() {
+                Some(0)
+            } else {
+                n.parse().ok()
+            }
+        })
+        .collect::<Option<_>>()
+        .unwrap_or_default();
+    let mut codes = Vec::new();
+    let mut i = 0;
+    while i < numbers.len() {
+        let color = |n: u16| u8::try_from(n).ok();
+        match (numbers[i], numbers.get(i + 1)) {
+            (selector @ (38 | 48 | 58), Some(5)) => {
+                if let Some(n) = numbers.get(i + 2).and_then(|n| color(*n)) {
+                    codes.push(match selector {
+                        38 => Ansi::Fg256(n),
+                        48 => Ansi::Bg256(n),
+                        _ => Ansi::UnderlineColor256(n),
+                    });
+                }
+                i += 3;
+            }
+            (selector @ (38 | 48 | 58), Some(2)) => {
+                let rgb: Option<Vec<u8>> = numbers
+                    .get(i + 2..i + 5)
+                    .and_then(|rgb| rgb.iter().map(|n| color(*n)).collect());
+                if let Some([r, g, b]) = rgb.as_deref() {
+                    codes.push(match selector {
+                        38 => Ansi::FgRgb(*r, *g, *b),
+                        48 => Ansi::BgRgb(*r, *g, *b),
+                        _ => Ansi::UnderlineColorRgb(*r, *g, *b),
+                    });
+                }
+                i += 5;
+            }
+            (code, _) => {
+                codes.extend(SIMPLE.iter().find(|ansi| ansi.code() == code));
+                i += 1;
+            }
+        }
+    }
+    codes
+}
+
+// A segment of a string containing escape sequences.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Segment<'a> {
+    // Plain text, including escape characters that start no complete sequence.
+    Text(&'a str),
+    // The parameters of a SGR sequence, e.g. `1;31` for `\x1b[1;31m`.
+    Sgr(&'a str),
+    // Any other complete CSI or OSC sequence, including its escape characters.
+    Other(&'a str),
+}
+
+// Iterator over the segments of a string, see `tokenize`.
+pub(crate) struct Tokens<'a> {
+    rest: &'a str,
+}
+
+// Splits a string into text, SGR sequences and other escape sequences.
+// This is the single scanner behind `utils::strip_ansi`, `utils::parse_spans`
+// and `Builder::from_ansi_string`.
+pub(crate) fn tokenize(input: &str) -> Tokens<'_> {
+    Tokens { rest: input }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let mut from = 0;
+        while let Some(offset) = self.rest[from..].find('\x1b') {
+            let start = from + offset;
+            if let Some((len, sgr)) = sequence_len(&self.rest[start..]) {
+                if start > 0 {
+                    let (text, rest) = self.rest.split_at(start);
+                    self.rest = rest;
+                    return Some(Segment::Text(text));
+                }
+                let (sequence, rest) = self.rest.split_at(len);
+                self.rest = rest;
+                return Some(if sgr {
+                    Segment::Sgr(&sequence[2..len - 1])
+                } else {
+                    Segment::Other(sequence)
+                });
+            }
+            from = start + 1;
+        }
+        let text = std::mem::take(&mut self.rest);
+        Some(Segment::Text(text))
+    }
+}
+
+// Returns the length of the complete escape sequence at the start of the input
+// and whether it is a SGR sequence with numeric parameters only.
+fn sequence_len(input: &str) -> Option<(usize, bool)> {
+    let bytes = input.as_bytes();
+    match bytes.get(1)? {
+        b'[' => {
+            let mut i = 2;
+            while matches!(bytes.get(i)?, 0x30..=0x3f) {
+                i += 1;
+            }
+            let params = &bytes[2..i];
+            while matches!(bytes.get(i)?, 0x20..=0x2f) {
+                i += 1;
+            }
+            let last = bytes[i];
+            if !matches!(last, 0x40..=0x7e) {
+                return None;
+            }
+            let sgr = last == b'm'
+                && params.len() == i - 2
+                && params.iter().all(|b| b.is_ascii_digit() || *b == b';');
+            Some((i + 1, sgr))
+        }
+        b']' => {
+            let mut i = 2;
+            loop {
+                match bytes.get(i)? {
+                    0x07 => return Some((i + 1, false)),
+                    0x1b if bytes.get(i + 1) == Some(&b'\\') => return Some((i + 2, false)),
+                    _ => i += 1,
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
+// The channel values of the 6x6x6 color cube in the 256 color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// Returns the squared Euclidean distance between two RGB colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> i32 {
+    let dr = a.0 as i32 - b.0 as i32;
+    let dg = a.1 as i32 - b.1 as i32;
+    let db = a.2 as i32 - b.2 as i32;
+    dr * dr + dg * dg + db * db
+}
+
+// Returns the index of the basic color closest to the RGB color.
+fn nearest_basic16(r: u8, g: u8, b: u8) -> usize {
+    (0..BASIC16.len())
+        .min_by_key(|&i| distance((r, g, b), BASIC16[i].2))
+        .unwrap()
+}
+
+// Returns the index of the 256 palette color closest to the RGB color.
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs())
+            .unwrap() as u8
+    };
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3) / 10).min(23)) as u8;
+    if distance((r, g, b), palette_256_rgb(gray)) < distance((r, g, b), palette_256_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+// Returns the RGB value of a 256 palette index.
+fn palette_256_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => BASIC16[n as usize].2,
+        16..=231 => {
+            let n = n - 16;
+            (
+                CUBE_LEVELS[(n / 36) as usize],
+                CUBE_LEVELS[(n / 6 % 6) as usize],
+                CUBE_LEVELS[(n % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + 10 * (n - 232);
+            (v, v, v)
+        }
+    }
+}
+
+// Converts a HSL color to RGB, clamping the inputs to their valid range.
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let h = h.clamp(0.0, 360.0) % 360.0;
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = l - c / 2.0;
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let scale = |v: f64| ((v + m) * 255.0).round() as u8;
+    (scale(r), scale(g), scale(b))
+}
+
+// Parses a hex color string into its RGB components.
+fn parse_hex(hex: &str) -> Result<(u8, u8, u8), ParseColorError> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(ParseColorError::InvalidDigit(c));
+    }
+    let digits: Vec<u8> = hex.chars().map(|c| c.to_digit(16).unwrap() as u8).collect();
+    match digits[..] {
+        [r, g, b] => Ok((r * 17, g * 17, b * 17)),
+        [r1, r2, g1, g2, b1, b2] => Ok((r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2)),
+        _ => Err(ParseColorError::InvalidLength(digits.len())),
+    }
 }
 
 // Implement the Display trait for the Color enum.
 impl Display for Ansi {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "\x1b[{}m", *self as i32)
+        write!(f, "\x1b[{}m", self.params())
+    }
+}
+
+// Implement the From trait to convert a RGB tuple into a color.
+// The tuple is always used as foreground color.
+impl From<(u8, u8, u8)> for Ansi {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Ansi::FgRgb(r, g, b)
+    }
+}
+
+// Implement the FromStr trait to parse names like `fg_red` or `FgRed`.
+// RGB variants have no name and can't be parsed.
+impl FromStr for Ansi {
+    type Err = ParseAnsiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .chars()
+            .filter(|c| *c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+        match name.as_str() {
+            "reset" => Ok(Ansi::Reset),
+            "bold" => Ok(Ansi::Bold),
+            "faint" => Ok(Ansi::Faint),
+            "italic" => Ok(Ansi::Italic),
+            "underline" => Ok(Ansi::Underline),
+            "blinkslow" => Ok(Ansi::BlinkSlow),
+            "blinkrapid" => Ok(Ansi::BlinkRapid),
+            "reversevideo" => Ok(Ansi::ReverseVideo),
+            "concealed" => Ok(Ansi::Concealed),
+            "crossedout" => Ok(Ansi::CrossedOut),
+            "doubleunderline" => Ok(Ansi::DoubleUnderline),
+            "overline" => Ok(Ansi::Overline),
+            "boldoff" => Ok(Ansi::BoldOff),
+            "faintoff" => Ok(Ansi::FaintOff),
+            "italicoff" => Ok(Ansi::ItalicOff),
+            "underlineoff" => Ok(Ansi::UnderlineOff),
+            "blinkoff" => Ok(Ansi::BlinkOff),
+            "reverseoff" => Ok(Ansi::ReverseOff),
+            "concealedoff" => Ok(Ansi::ConcealedOff),
+            "crossedoutoff" => Ok(Ansi::CrossedOutOff),
+            "fgblack" => Ok(Ansi::FgBlack),
+            "fgred" => Ok(Ansi::FgRed),
+            "fggreen" => Ok(Ansi::FgGreen),
+            "fgyellow" => Ok(Ansi::FgYellow),
+            "fgblue" => Ok(Ansi::FgBlue),
+            "fgmagenta" => Ok(Ansi::FgMagenta),
+            "fgcyan" => Ok(Ansi::FgCyan),
+            "fgwhite" => Ok(Ansi::FgWhite),
+            "fghiblack" => Ok(Ansi::FgHiBlack),
+            "fghired" => Ok(Ansi::FgHiRed),
+            "fghigreen" => Ok(Ansi::FgHiGreen),
+            "fghiyellow" => Ok(Ansi::FgHiYellow),
+            "fghiblue" => Ok(Ansi::FgHiBlue),
+            "fghimagenta" => Ok(Ansi::FgHiMagenta),
+            "fghicyan" => Ok(Ansi::FgHiCyan),
+            "fghiwhite" => Ok(Ansi::FgHiWhite),
+            "fgdefault" => Ok(Ansi::FgDefault),
+            "bgblack" => Ok(Ansi::BgBlack),
+            "bgred" => Ok(Ansi::BgRed),
+            "bggreen" => Ok(Ansi::BgGreen),
+            "bgyellow" => Ok(Ansi::BgYellow),
+            "bgblue" => Ok(Ansi::BgBlue),
+            "bgmagenta" => Ok(Ansi::BgMagenta),
+            "bgcyan" => Ok(Ansi::BgCyan),
+            "bgwhite" => Ok(Ansi::BgWhite),
+            "bghiblack" => Ok(Ansi::BgHiBlack),
+            "bghired" => Ok(Ansi::BgHiRed),
+            "bghigreen" => Ok(Ansi::BgHiGreen),
+            "bghiyellow" => Ok(Ansi::BgHiYellow),
+            "bghiblue" => Ok(Ansi::BgHiBlue),
+            "bghimagenta" => Ok(Ansi::BgHiMagenta),
+            "bghicyan" => Ok(Ansi::BgHiCyan),
+            "bghiwhite" => Ok(Ansi::BgHiWhite),
+            "bgdefault" => Ok(Ansi::BgDefault),
+            "underlinecolordefault" => Ok(Ansi::UnderlineColorDefault),
+            _ => Err(ParseAnsiError(s.to_string())),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Ansi;
+    use super::{parse_params, tokenize, Ansi, ParseAnsiError, ParseColorError, Segment};
 
     #[test]
     fn test_fg_colors() {
@@ -100,4 +980,281 @@ mod tests {
         let bold = Ansi::Bold;
         assert_eq!(format!("{}", bold), "\x1b[1m");
     }
+
+    #[test]
+    fn test_rgb_colors() {
+        let fg_orange = Ansi::FgRgb(255, 128, 0);
+        assert_eq!(format!("{}", fg_orange), "\x1b[38;2;255;128;0m");
+
+        let bg_teal = Ansi::BgRgb(0, 128, 128);
+        assert_eq!(format!("{}", bg_teal), "\x1b[48;2;0;128;128m");
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(Ansi::from_hex("#ff8800"), Ok(Ansi::FgRgb(255, 136, 0)));
+        assert_eq!(Ansi::from_hex("ff8800"), Ok(Ansi::FgRgb(255, 136, 0)));
+        assert_eq!(Ansi::from_hex("#FF8800"), Ok(Ansi::FgRgb(255, 136, 0)));
+        assert_eq!(Ansi::from_hex("#f80"), Ok(Ansi::FgRgb(255, 136, 0)));
+        assert_eq!(Ansi::from_hex_bg("#0a0B0c"), Ok(Ansi::BgRgb(10, 11, 12)));
+    }
+
+    #[test]
+    fn test_from_hex_malformed() {
+        assert_eq!(Ansi::from_hex(""), Err(ParseColorError::InvalidLength(0)));
+        assert_eq!(Ansi::from_hex("#"), Err(ParseColorError::InvalidLength(0)));
+        assert_eq!(
+            Ansi::from_hex("#ff88"),
+            Err(ParseColorError::InvalidLength(4))
+        );
+        assert_eq!(
+            Ansi::from_hex("#ff880g"),
+            Err(ParseColorError::InvalidDigit('g'))
+        );
+        assert_eq!(
+            Ansi::from_hex("##ff8800"),
+            Err(ParseColorError::InvalidDigit('#'))
+        );
+        assert_eq!(
+            ParseColorError::InvalidDigit('g').to_string(),
+            "invalid hex digit 'g'"
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("fg_red".parse::<Ansi>(), Ok(Ansi::FgRed));
+        assert_eq!("FgRed".parse::<Ansi>(), Ok(Ansi::FgRed));
+        assert_eq!("bold".parse::<Ansi>(), Ok(Ansi::Bold));
+        assert_eq!("bg_hi_white".parse::<Ansi>(), Ok(Ansi::BgHiWhite));
+        assert_eq!("CROSSED_OUT".parse::<Ansi>(), Ok(Ansi::CrossedOut));
+        assert_eq!("reset".parse::<Ansi>(), Ok(Ansi::Reset));
+    }
+
+    #[test]
+    fn test_from_str_unknown() {
+        let err = "fg_purple".parse::<Ansi>().unwrap_err();
+        assert_eq!(err, ParseAnsiError("fg_purple".to_string()));
+        assert_eq!(err.to_string(), "unknown ANSI name 'fg_purple'");
+        assert!("".parse::<Ansi>().is_err());
+    }
+
+    #[test]
+    fn test_line_codes() {
+        let double_underline = Ansi::DoubleUnderline;
+        assert_eq!(format!("{}", double_underline), "\x1b[21m");
+
+        let overline = Ansi::Overline;
+        assert_eq!(format!("{}", overline), "\x1b[53m");
+    }
+
+    #[test]
+    fn test_off_codes() {
+        assert_eq!(format!("{}", Ansi::BoldOff), "\x1b[22m");
+        assert_eq!(format!("{}", Ansi::FaintOff), "\x1b[22m");
+        assert_eq!(format!("{}", Ansi::ItalicOff), "\x1b[23m");
+        assert_eq!(format!("{}", Ansi::UnderlineOff), "\x1b[24m");
+        assert_eq!(format!("{}", Ansi::BlinkOff), "\x1b[25m");
+        assert_eq!(format!("{}", Ansi::ReverseOff), "\x1b[27m");
+        assert_eq!(format!("{}", Ansi::ConcealedOff), "\x1b[28m");
+        assert_eq!(format!("{}", Ansi::CrossedOutOff), "\x1b[29m");
+        assert_eq!(format!("{}", Ansi::FgDefault), "\x1b[39m");
+        assert_eq!(format!("{}", Ansi::BgDefault), "\x1b[49m");
+    }
+
+    #[test]
+    fn test_code() {
+        assert_eq!(Ansi::FgRed.code(), 31);
+        assert_eq!(Ansi::Reset.code(), 0);
+        assert_eq!(Ansi::BgHiWhite.code(), 107);
+        assert_eq!(Ansi::FgRgb(1, 2, 3).code(), 38);
+        assert_eq!(Ansi::BgRgb(1, 2, 3).code(), 48);
+    }
+
+    #[test]
+    fn test_to_basic() {
+        assert_eq!(Ansi::FgRgb(255, 0, 0).to_basic(), Ansi::FgRed);
+        assert_eq!(Ansi::FgRgb(80, 80, 80).to_basic(), Ansi::FgHiBlack);
+        assert_eq!(Ansi::FgRgb(255, 255, 255).to_basic(), Ansi::FgHiWhite);
+        assert_eq!(Ansi::BgRgb(0, 160, 0).to_basic(), Ansi::BgGreen);
+        assert_eq!(Ansi::FgCyan.to_basic(), Ansi::FgCyan);
+    }
+
+    #[test]
+    fn test_256_colors() {
+        assert_eq!(format!("{}", Ansi::Fg256(208)), "\x1b[38;5;208m");
+        assert_eq!(format!("{}", Ansi::Bg256(17)), "\x1b[48;5;17m");
+        assert_eq!(Ansi::Fg256(208).code(), 38);
+    }
+
+    #[test]
+    fn test_underline_colors() {
+        assert_eq!(
+            format!("{}", Ansi::UnderlineColorRgb(255, 0, 0)),
+            "\x1b[58;2;255;0;0m"
+        );
+        assert_eq!(
+            format!("{}", Ansi::UnderlineColor256(208)),
+            "\x1b[58;5;208m"
+        );
+        assert_eq!(format!("{}", Ansi::UnderlineColorDefault), "\x1b[59m");
+        assert_eq!(
+            Ansi::UnderlineColorRgb(255, 0, 0).to_256(),
+            Ansi::UnderlineColor256(196)
+        );
+        assert_eq!(
+            parse_params("58;5;208;59"),
+            vec![Ansi::UnderlineColor256(208), Ansi::UnderlineColorDefault]
+        );
+    }
+
+    #[test]
+    fn test_to_256() {
+        assert_eq!(Ansi::FgRgb(255, 0, 0).to_256(), Ansi::Fg256(196));
+        assert_eq!(Ansi::FgRgb(0, 0, 0).to_256(), Ansi::Fg256(16));
+        assert_eq!(Ansi::FgRgb(128, 128, 128).to_256(), Ansi::Fg256(244));
+        assert_eq!(Ansi::BgRgb(255, 255, 255).to_256(), Ansi::Bg256(231));
+        assert_eq!(Ansi::Bold.to_256(), Ansi::Bold);
+    }
+
+    #[test]
+    fn test_256_to_basic() {
+        assert_eq!(Ansi::Fg256(1).to_basic(), Ansi::FgRed);
+        assert_eq!(Ansi::Bg256(9).to_basic(), Ansi::BgHiRed);
+        assert_eq!(Ansi::Fg256(16).to_basic(), Ansi::FgBlack);
+        assert_eq!(Ansi::Fg256(244).to_basic(), Ansi::FgWhite);
+        assert_eq!(Ansi::Fg256(255).to_basic(), Ansi::FgHiWhite);
+    }
+
+    #[test]
+    fn test_from_hsl() {
+        assert_eq!(Ansi::from_hsl(0.0, 1.0, 0.5), Ansi::FgRgb(255, 0, 0));
+        assert_eq!(Ansi::from_hsl(120.0, 1.0, 0.5), Ansi::FgRgb(0, 255, 0));
+        assert_eq!(Ansi::from_hsl(360.0, 1.0, 0.5), Ansi::FgRgb(255, 0, 0));
+        assert_eq!(Ansi::from_hsl(30.0, 1.0, 0.5), Ansi::FgRgb(255, 128, 0));
+        assert_eq!(Ansi::from_hsl(0.0, 0.0, 0.5), Ansi::FgRgb(128, 128, 128));
+        assert_eq!(Ansi::from_hsl_bg(240.0, 1.0, 0.5), Ansi::BgRgb(0, 0, 255));
+    }
+
+    #[test]
+    fn test_from_hsl_clamps() {
+        assert_eq!(Ansi::from_hsl(-10.0, 2.0, 0.5), Ansi::FgRgb(255, 0, 0));
+        assert_eq!(Ansi::from_hsl(500.0, 1.0, 0.5), Ansi::FgRgb(255, 0, 0));
+        assert_eq!(Ansi::from_hsl(0.0, 1.0, 1.5), Ansi::FgRgb(255, 255, 255));
+        assert_eq!(Ansi::from_hsl(0.0, 1.0, -1.0), Ansi::FgRgb(0, 0, 0));
+    }
+
+    #[test]
+    fn test_parse_params() {
+        assert_eq!(parse_params(""), vec![Ansi::Reset]);
+        assert_eq!(parse_params("0"), vec![Ansi::Reset]);
+        assert_eq!(parse_params("1;31"), vec![Ansi::Bold, Ansi::FgRed]);
+        assert_eq!(parse_params("22"), vec![Ansi::BoldOff]);
+        assert_eq!(
+            parse_params("38;5;208;48;2;1;2;3"),
+            vec![Ansi::Fg256(208), Ansi::BgRgb(1, 2, 3)]
+        );
+        assert_eq!(parse_params("1;77;4"), vec![Ansi::Bold, Ansi::Underline]);
+        assert_eq!(parse_params("38;2;1"), vec![]);
+    }
+
+    #[test]
+    fn test_categories() {
+        assert!(Ansi::FgHiCyan.is_foreground());
+        assert!(!Ansi::FgHiCyan.is_background());
+        assert!(Ansi::BgRgb(1, 2, 3).is_background());
+        assert!(!Ansi::BgRgb(1, 2, 3).is_style());
+        assert!(Ansi::Underline.is_style());
+        assert!(Ansi::UnderlineColor256(1).is_style());
+        assert!(!Ansi::Underline.is_foreground());
+        assert!(Ansi::Reset.is_reset());
+        assert!(!Ansi::Reset.is_style());
+    }
+
+    #[test]
+    fn test_gray() {
+        assert_eq!(Ansi::gray(0), Ansi::Fg256(232));
+        assert_eq!(Ansi::gray(23), Ansi::Fg256(255));
+        assert_eq!(Ansi::gray(200), Ansi::Fg256(255));
+        assert_eq!(Ansi::gray_bg(0), Ansi::Bg256(232));
+        assert_eq!(Ansi::gray_bg(23), Ansi::Bg256(255));
+    }
+
+    #[test]
+    fn test_cube() {
+        assert_eq!(Ansi::cube(0, 0, 0), Ansi::Fg256(16));
+        assert_eq!(Ansi::cube(5, 0, 0), Ansi::Fg256(196));
+        assert_eq!(Ansi::cube(5, 5, 5), Ansi::Fg256(231));
+        assert_eq!(Ansi::cube(9, 1, 200), Ansi::Fg256(207));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn test_random() {
+        for _ in 0..100 {
+            let fg = Ansi::random_fg();
+            assert!(fg.is_foreground());
+            assert_eq!(fg.to_basic(), fg);
+            assert_ne!(fg, Ansi::FgDefault);
+            let bg = Ansi::random_bg();
+            assert!(bg.is_background());
+            assert_eq!(bg.to_basic(), bg);
+            assert_ne!(bg, Ansi::BgDefault);
+        }
+    }
+
+    #[test]
+    fn test_from_rgb_tuple() {
+        let red = Ansi::from((255, 0, 0));
+        assert_eq!(red, Ansi::FgRgb(255, 0, 0));
+        assert_eq!(format!("{}", red), "\x1b[38;2;255;0;0m");
+        let teal: Ansi = (0, 128, 128).into();
+        assert_eq!(teal, Ansi::FgRgb(0, 128, 128));
+    }
+
+    #[test]
+    fn test_blink_off() {
+        assert_eq!(format!("{}", Ansi::BlinkOff), "\x1b[25m");
+        assert_eq!(Ansi::BlinkOff.code(), 25);
+        assert_eq!("blink_off".parse::<Ansi>(), Ok(Ansi::BlinkOff));
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let segments: Vec<Segment> = tokenize("a\x1b[1;31mb\x1b[Kc\x1b]0;t\x07\x1b[m").collect();
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Text("a"),
+                Segment::Sgr("1;31"),
+                Segment::Text("b"),
+                Segment::Other("\x1b[K"),
+                Segment::Text("c"),
+                Segment::Other("\x1b]0;t\x07"),
+                Segment::Sgr(""),
+            ]
+        );
+
+        // Unterminated sequences and lone escapes stay text.
+        assert_eq!(
+            tokenize("x\x1b[31").collect::<Vec<_>>(),
+            vec![Segment::Text("x\x1b[31")]
+        );
+        assert_eq!(
+            tokenize("\x1b]8;;url").collect::<Vec<_>>(),
+            vec![Segment::Text("\x1b]8;;url")]
+        );
+        assert_eq!(
+            tokenize("\x1b\x1b[1m").collect::<Vec<_>>(),
+            vec![Segment::Text("\x1b"), Segment::Sgr("1")]
+        );
+        assert_eq!(
+            tokenize("\x1b[?25l\x1b]8;;u\x1b\\").collect::<Vec<_>>(),
+            vec![
+                Segment::Other("\x1b[?25l"),
+                Segment::Other("\x1b]8;;u\x1b\\")
+            ]
+        );
+        assert_eq!(tokenize("").next(), None);
+    }
 }
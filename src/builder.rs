@@ -1,11 +1,93 @@
-use crate::ansi::Ansi;
-use crate::utils;
+use crate::ansi::{self, Ansi, ParseColorError, Segment};
+use crate::style::Style;
+use crate::utils::{self, ColorLevel, Stream};
 use std::fmt::{self, Display};
+use std::io;
+use std::ops::{Add, Deref, DerefMut};
+use std::sync::Mutex;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Entity {
     Text(String),
     Ansi(Ansi),
+    Link { text: String, url: String },
+    Control(String),
+    Raw(String),
+}
+
+// The entities of a builder together with the last rendered output.
+// Any mutable access drops the rendered output, so it can't get stale.
+#[derive(Default)]
+struct Content {
+    entities: Vec<Entity>,
+    cache: Mutex<Option<(ColorLevel, String)>>,
+}
+
+impl From<Vec<Entity>> for Content {
+    fn from(entities: Vec<Entity>) -> Self {
+        Self {
+            entities,
+            cache: Mutex::new(None),
+        }
+    }
+}
+
+impl Clone for Content {
+    fn clone(&self) -> Self {
+        Self::from(self.entities.clone())
+    }
+}
+
+impl fmt::Debug for Content {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.entities.fmt(f)
+    }
+}
+
+impl PartialEq for Content {
+    fn eq(&self, other: &Self) -> bool {
+        self.entities == other.entities
+    }
+}
+
+impl PartialEq<Vec<Entity>> for Content {
+    fn eq(&self, other: &Vec<Entity>) -> bool {
+        &self.entities == other
+    }
+}
+
+impl Deref for Content {
+    type Target = Vec<Entity>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.entities
+    }
+}
+
+impl DerefMut for Content {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        *self.cache.get_mut().unwrap_or_else(|e| e.into_inner()) = None;
+        &mut self.entities
+    }
+}
+
+impl<'a> IntoIterator for &'a Content {
+    type Item = &'a Entity;
+    type IntoIter = std::slice::Iter<'a, Entity>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entities.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Content {
+    type Item = &'a mut Entity;
+    type IntoIter = std::slice::IterMut<'a, Entity>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.deref_mut().iter_mut()
+    }
 }
 
 /// A builder for creating styled and colored text.
@@ -24,9 +106,12 @@ pub enum Entity {
 /// ```
 #[derive(Debug, Clone)]
 pub struct Builder {
-    content: Vec<Entity>,
+    content: Content,
     no_color: bool,
     force_color: bool,
+    stream: Stream,
+    auto_downsample: bool,
+    partial_utf8: Vec<u8>,
 }
 
 impl Display for Builder {
@@ -36,6 +121,86 @@ impl Display for Builder {
     }
 }
 
+// Implement the Add trait to concatenate two builders.
+// The color settings follow the left operand.
+impl Add for Builder {
+    type Output = Builder;
+
+    fn add(mut self, other: Builder) -> Builder {
+        self.content.extend(other.content.entities);
+        self
+    }
+}
+
+// Implement the FromIterator trait to collect entities into a builder.
+// The color settings are the same as for `Builder::new`.
+impl FromIterator<Entity> for Builder {
+    fn from_iter<I: IntoIterator<Item = Entity>>(iter: I) -> Self {
+        let mut builder = Builder::new();
+        builder.extend(iter);
+        builder
+    }
+}
+
+impl Extend<Entity> for Builder {
+    fn extend<I: IntoIterator<Item = Entity>>(&mut self, iter: I) {
+        self.content.extend(iter);
+    }
+}
+
+// Serializes the content of the builder as a list of entities.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Builder {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.content.serialize(serializer)
+    }
+}
+
+// Deserializes a list of entities into a builder.
+// The color settings are the same as for `Builder::new`.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Builder {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Entity>::deserialize(deserializer).map(Builder::from_iter)
+    }
+}
+
+impl<'a> IntoIterator for &'a Builder {
+    type Item = &'a Entity;
+    type IntoIter = std::slice::Iter<'a, Entity>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Implement the io::Write trait to append bytes as text.
+// Incomplete UTF-8 sequences at the end are kept until the next write.
+impl io::Write for Builder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let start = self.partial_utf8.len();
+        self.partial_utf8.extend_from_slice(buf);
+        let valid = match std::str::from_utf8(&self.partial_utf8) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => {
+                self.partial_utf8.truncate(start);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+            }
+        };
+        if valid > 0 {
+            let bytes: Vec<u8> = self.partial_utf8.drain(..valid).collect();
+            self.content
+                .push(Entity::Text(String::from_utf8(bytes).expect("valid UTF-8")));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 impl Default for Builder {
     fn default() -> Self {
         Self::new()
@@ -54,519 +219,587 @@ impl Builder {
     /// ```
     pub fn new() -> Self {
         Self {
-            content: Vec::new(),
+            content: Content::default(),
             no_color: utils::no_color(),
             force_color: false,
+            stream: Stream::Stdout,
+            auto_downsample: false,
+            partial_utf8: Vec::new(),
         }
     }
 
-    // This function is for testing purposes only.
-    #[doc(hidden)]
-    pub fn force_color(&mut self) -> &mut Self {
-        self.force_color = true;
-        self
-    }
-
-    /// Appends text to the builder.
+    /// Creates a new `Builder` with space for at least `n` entities.
+    /// The color settings are the same as for `Builder::new`.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.text("Hello, world!");
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Text("Hello, world!".to_string()));
+    /// let builder = Builder::with_capacity(16);
+    /// assert!(builder.is_empty());
     /// ```
-    pub fn text(&mut self, text: &str) -> &mut Self {
-        self.content.push(Entity::Text(text.to_string()));
-        self
+    pub fn with_capacity(n: usize) -> Self {
+        Self {
+            content: Content::from(Vec::with_capacity(n)),
+            ..Self::new()
+        }
     }
 
-    /// Prints the content of the `Builder`.
-    /// This will print the content to stdout.
+    /// Creates a new `Builder` from a string containing SGR escape sequences.
+    /// Each sequence becomes one ANSI entity per code and the text in between becomes text.
+    /// Unknown or malformed codes are skipped, other escape sequences are kept as text.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
-    /// builder.print();
+    /// let builder = Builder::from_ansi_string("\x1b[1;31mhi\x1b[0m");
+    /// assert_eq!(builder.content_raw(), vec![
+    ///     Entity::Ansi(Ansi::Bold),
+    ///     Entity::Ansi(Ansi::FgRed),
+    ///     Entity::Text("hi".to_string()),
+    ///     Entity::Ansi(Ansi::Reset),
+    /// ]);
     /// ```
-    pub fn print(&self) {
-        print!("{}", self);
+    pub fn from_ansi_string(s: &str) -> Self {
+        let mut builder = Self::new();
+        let mut text = String::new();
+        for segment in ansi::tokenize(s) {
+            match segment {
+                Segment::Sgr(params) => {
+                    if !text.is_empty() {
+                        builder.text(&std::mem::take(&mut text));
+                    }
+                    for code in ansi::parse_params(params) {
+                        builder.ansi(code);
+                    }
+                }
+                Segment::Text(segment) | Segment::Other(segment) => text.push_str(segment),
+            }
+        }
+        if !text.is_empty() {
+            builder.text(&text);
+        }
+        builder
     }
 
-    /// Println the content of the `Builder`.
-    /// This will print the content to stdout with newline.
+    /// Creates a new `Builder` targeting stderr.
+    /// Color detection checks stderr instead of stdout.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
-    /// builder.println();
+    /// let mut builder = Builder::new_stderr();
+    /// builder.fg_yellow().text("warning").reset().eprintln();
     /// ```
-    pub fn println(&self) {
-        println!("{}", self);
+    pub fn new_stderr() -> Self {
+        Self {
+            content: Content::default(),
+            no_color: utils::no_color_for(Stream::Stderr),
+            force_color: false,
+            stream: Stream::Stderr,
+            auto_downsample: false,
+            partial_utf8: Vec::new(),
+        }
     }
 
-    /// Returns the content of the `Builder` as a `String`.
-    /// This will return the content as a `String` without any ANSI codes.
+    /// Creates a new `Builder` that never emits ANSI codes.
+    /// Only the text is rendered, regardless of environment and TTY.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
-    /// assert_eq!(builder.to_string(), "Hello, world! This is bold text!");
+    /// let mut builder = Builder::new_no_color();
+    /// builder.bold().text("bold").reset();
+    /// assert_eq!(builder.as_string(), "bold");
     /// ```
-    pub fn as_string(&self) -> String {
-        let mut content = String::new();
-        if self.no_color && !self.force_color {
-            for entity in &self.content {
-                match entity {
-                    Entity::Text(text) => content.push_str(text),
-                    Entity::Ansi(_) => (),
-                }
-            }
-            return content;
-        }
-        if self.content.last() != Some(&Entity::Ansi(Ansi::Reset)) {
-            content.push_str(&Ansi::Reset.to_string());
-        }
-        for entity in &self.content {
-            match entity {
-                Entity::Text(text) => content.push_str(text),
-                Entity::Ansi(ansi) => content.push_str(&ansi.to_string()),
-            }
+    pub fn new_no_color() -> Self {
+        Self {
+            no_color: true,
+            ..Self::new()
         }
-        content
     }
 
-    /// Returns the raw content of the `Builder`.
-    /// This is useful for debugging purposes.
+    /// Creates a new `Builder` that always emits ANSI codes.
+    /// This is the same as calling `force_color` on a new `Builder`.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.text("Hello, world!");
-    /// assert_eq!(builder.content_raw().len(), 1);
+    /// let mut builder = Builder::new_force_color();
+    /// builder.bold().text("bold").reset();
+    /// assert_eq!(builder.as_string(), "\x1b[1mbold\x1b[0m");
     /// ```
-    pub fn content_raw(&self) -> Vec<Entity> {
-        self.content.clone()
+    pub fn new_force_color() -> Self {
+        Self {
+            force_color: true,
+            ..Self::new()
+        }
     }
 
-    /// Appends an ANSI code to the builder.
-    /// This is useful for custom ANSI codes.
+    /// Creates a new `Builder` styled as an error message.
+    /// The text is bold red and followed by a reset.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.ansi(Ansi::BgBlue);
-    /// assert_eq!(builder.content_raw().len(), 1);
-    pub fn ansi(&mut self, ansi: Ansi) -> &mut Self {
-        self.content.push(Entity::Ansi(ansi));
-        self
+    /// Builder::error("message").println();
+    /// ```
+    pub fn error(text: &str) -> Self {
+        let mut builder = Self::new();
+        builder.bold().fg_red().text(text).reset();
+        builder
     }
 
-    /// Appends a reset ANSI code.
-    /// This will reset all styles and colors.
+    /// Creates a new `Builder` styled as a warning message.
+    /// The text is yellow and followed by a reset.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.reset();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Reset));
+    /// Builder::warn("message").println();
     /// ```
-    pub fn reset(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::Reset));
-        self
+    pub fn warn(text: &str) -> Self {
+        let mut builder = Self::new();
+        builder.fg_yellow().text(text).reset();
+        builder
     }
 
-    /// Appends a bold ANSI code.
-    /// This will make the text bold.
+    /// Creates a new `Builder` styled as an info message.
+    /// The text is blue and followed by a reset.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.bold();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Bold));
+    /// Builder::info("message").println();
     /// ```
-    pub fn bold(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::Bold));
-        self
+    pub fn info(text: &str) -> Self {
+        let mut builder = Self::new();
+        builder.fg_blue().text(text).reset();
+        builder
     }
 
-    /// Appends a faint ANSI code.
-    /// This will make the text faint.
+    /// Creates a new `Builder` styled as a success message.
+    /// The text is green and followed by a reset.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.faint();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Faint));
+    /// Builder::success("message").println();
     /// ```
-    pub fn faint(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::Faint));
-        self
+    pub fn success(text: &str) -> Self {
+        let mut builder = Self::new();
+        builder.fg_green().text(text).reset();
+        builder
     }
 
-    /// Appends a italic ANSI code.
-    /// This will make the text faint.
+    /// Creates a new `Builder` with the text in the given color.
+    /// The text is followed by a reset.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
     /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.italic();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Italic));
+    /// Builder::colored("message", Ansi::FgCyan).println();
     /// ```
-    pub fn italic(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::Italic));
-        self
+    pub fn colored(text: &str, color: Ansi) -> Self {
+        let mut builder = Self::new();
+        builder.ansi(color).text(text).reset();
+        builder
     }
 
-    /// Appends a underline ANSI code.
-    /// This will make the text underlined.
+    /// Forces the output of ANSI codes.
+    /// This will emit ANSI codes even if `NO_COLOR` is set or stdout is not a TTY.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.underline();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Underline));
+    /// builder.force_color().bold().text("bold").reset();
+    /// assert_eq!(builder.as_string(), "\x1b[1mbold\x1b[0m");
     /// ```
-    pub fn underline(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::Underline));
+    pub fn force_color(&mut self) -> &mut Self {
+        self.force_color = true;
         self
     }
 
-    /// Appends a slow blink ANSI code.
-    /// This will make the text blink slowly.
+    /// Enables or disables the output of ANSI codes, regardless of environment and TTY.
+    /// This overrides the detection done in `Builder::new` and any earlier `force_color` call.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.blink_slow();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BlinkSlow));
+    /// builder.bold().text("bold").reset();
+    /// assert_eq!(builder.set_color_enabled(false).as_string(), "bold");
+    /// assert_eq!(builder.set_color_enabled(true).as_string(), "\x1b[1mbold\x1b[0m");
     /// ```
-    pub fn blink_slow(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BlinkSlow));
+    pub fn set_color_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.no_color = !enabled;
+        self.force_color = enabled;
         self
     }
 
-    /// Appends a rapid blink ANSI code.
-    /// This will make the text blink rapidly.
+    /// Returns whether the builder will emit ANSI codes.
+    /// This is the case unless colors are disabled and not forced.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.blink_rapid();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BlinkRapid));
+    /// assert!(!Builder::new_no_color().color_enabled());
+    /// assert!(Builder::new_force_color().color_enabled());
     /// ```
-    pub fn blink_rapid(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BlinkRapid));
-        self
+    pub fn color_enabled(&self) -> bool {
+        !self.no_color || self.force_color
     }
 
-    /// Appends a reverse video ANSI code.
-    /// This will reverse the text and background colors.
+    /// Appends text to the builder.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
     /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.reverse_video();
+    /// builder.text("Hello, world!");
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::ReverseVideo));
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("Hello, world!".to_string()));
     /// ```
-    pub fn reverse_video(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::ReverseVideo));
+    pub fn text(&mut self, text: &str) -> &mut Self {
+        self.content.push(Entity::Text(text.to_string()));
         self
     }
 
-    /// Appends a concealed ANSI code.
-    /// This will hide the text.
+    /// Appends formatted text to the builder.
+    /// This avoids a separate `format!` before calling `text`.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
     /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.concealed();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Concealed));
+    /// builder.text_fmt(format_args!("x={}", 1));
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("x=1".to_string()));
     /// ```
-    pub fn concealed(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::Concealed));
+    pub fn text_fmt(&mut self, args: fmt::Arguments) -> &mut Self {
+        self.content.push(Entity::Text(args.to_string()));
         self
     }
 
-    /// Appends a crossed out ANSI code.
-    /// This will cross out the text.
+    /// Prints the content of the `Builder`.
+    /// This will print the content to stdout, downgrading colors
+    /// to the level detected by `utils::color_level`.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.crossed_out();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::CrossedOut));
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// builder.print();
     /// ```
-    pub fn crossed_out(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::CrossedOut));
-        self
+    pub fn print(&self) {
+        self.print_at(self.render_level());
     }
 
-    /// Appends a black foreground ANSI code.
-    /// This will set the text color to black.
+    /// Prints the content of the `Builder` at the given color level.
+    /// This will print the content to stdout, downgrading colors
+    /// the given level does not support.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
+    /// use rcolors::utils::ColorLevel;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_black();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgBlack));
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// builder.print_at(ColorLevel::Ansi16);
     /// ```
-    pub fn fg_black(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgBlack));
-        self
+    pub fn print_at(&self, level: ColorLevel) {
+        utils::enable_windows_ansi_once();
+        print!("{}", self.render_at(level));
     }
 
-    /// Appends a red foreground ANSI code.
-    /// This will set the text color to red.
+    /// Prints the content of the `Builder` into an already locked stdout.
+    /// This avoids locking stdout for every builder when printing many of them.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.fg_red();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgRed));
+    /// let stdout = std::io::stdout();
+    /// let mut lock = stdout.lock();
+    /// for i in 0..3 {
+    ///     let mut builder = Builder::new();
+    ///     builder.fg_green().text_fmt(format_args!("line {}\n", i));
+    ///     builder.print_locked(&mut lock).unwrap();
+    /// }
     /// ```
-    pub fn fg_red(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgRed));
-        self
+    pub fn print_locked(&self, lock: &mut io::StdoutLock<'_>) -> io::Result<()> {
+        self.print_into(lock)
     }
 
-    /// Appends a green foreground ANSI code.
-    /// This will set the text color to green.
+    // Writes the content like `print` does, but into the given writer.
+    fn print_into<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        utils::enable_windows_ansi_once();
+        w.write_all(self.render_at(self.render_level()).as_bytes())
+    }
+
+    /// Println the content of the `Builder`.
+    /// This will print the content to stdout with newline.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_green();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgGreen));
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// builder.println();
     /// ```
-    pub fn fg_green(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgGreen));
-        self
+    pub fn println(&self) {
+        utils::enable_windows_ansi_once();
+        println!("{}", self.render_at(self.render_level()));
     }
 
-    /// Appends a yellow foreground ANSI code.
-    /// This will set the text color to yellow.
+    /// Eprints the content of the `Builder`.
+    /// This will print the content to stderr.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.fg_yellow();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgYellow));
+    /// let mut builder = Builder::new_stderr();
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// builder.eprint();
     /// ```
-    pub fn fg_yellow(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgYellow));
-        self
+    pub fn eprint(&self) {
+        utils::enable_windows_ansi_once();
+        eprint!("{}", self.render_at(self.render_level()));
     }
 
-    /// Appends a blue foreground ANSI code.
-    /// This will set the text color to blue.
+    /// Eprintln the content of the `Builder`.
+    /// This will print the content to stderr with newline.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
-    /// let mut builder = Builder::new();
-    /// builder.fg_blue();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgBlue));
+    /// let mut builder = Builder::new_stderr();
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// builder.eprintln();
     /// ```
-    pub fn fg_blue(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgBlue));
-        self
+    pub fn eprintln(&self) {
+        utils::enable_windows_ansi_once();
+        eprintln!("{}", self.render_at(self.render_level()));
     }
 
-    /// Appends a magenta foreground ANSI code.
-    /// This will set the text color to magenta.
+    /// Returns the content of the `Builder` as a `String`.
+    /// This is the canonical renderer, `Display` and `to_string` delegate to it.
+    /// ANSI codes are left out if colors are disabled, and codes still active at the end are reset.
+    /// Unlike the print methods, colors are only downgraded to the detected support
+    /// if `auto_downsample` is enabled.
+    /// Prefer `as_string` instead of `to_string` to avoid confusion with the `ToString` trait.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_magenta();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgMagenta));
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// assert_eq!(builder.as_string(), builder.to_string());
     /// ```
-    pub fn fg_magenta(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgMagenta));
-        self
+    pub fn as_string(&self) -> String {
+        self.render_at(self.output_level())
     }
 
-    /// Appends a cyan foreground ANSI code.
-    /// This will set the text color to cyan.
+    /// Returns the content of the `Builder` as UTF-8 bytes.
+    /// This is the same output as `as_string`, ready for byte-oriented sinks.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_cyan();
-    /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgCyan));
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// assert_eq!(builder.to_bytes(), builder.as_string().into_bytes());
     /// ```
-    pub fn fg_cyan(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgCyan));
-        self
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.as_string().into_bytes()
     }
 
-    /// Appends a white foreground ANSI code.
-    /// This will set the text color to white.
+    // Returns the color level used by `as_string`.
+    fn output_level(&self) -> ColorLevel {
+        if !self.color_enabled() {
+            ColorLevel::None
+        } else if self.auto_downsample {
+            self.render_level()
+        } else {
+            ColorLevel::TrueColor
+        }
+    }
+
+    // Returns the color level used by the print methods.
+    fn render_level(&self) -> ColorLevel {
+        if !self.color_enabled() {
+            return ColorLevel::None;
+        }
+        match utils::color_level_for(self.stream) {
+            // Colors are forced, so render them as they are.
+            ColorLevel::None => ColorLevel::TrueColor,
+            level => level,
+        }
+    }
+
+    // Renders the content at the level, reusing the last result if nothing changed.
+    fn render_at(&self, level: ColorLevel) -> String {
+        let mut cache = self.content.cache.lock().unwrap_or_else(|e| e.into_inner());
+        match &*cache {
+            Some((cached, rendered)) if *cached == level => rendered.clone(),
+            _ => {
+                let rendered = Self::render(&self.content, level);
+                *cache = Some((level, rendered.clone()));
+                rendered
+            }
+        }
+    }
+
+    // Renders the entities, downgrading colors the level does not support.
+    fn render(entities: &[Entity], level: ColorLevel) -> String {
+        let mut content = String::new();
+        if entities.is_empty() {
+            return content;
+        }
+        if level == ColorLevel::None {
+            for entity in entities {
+                match entity {
+                    Entity::Text(text) | Entity::Link { text, .. } | Entity::Raw(text) => {
+                        content.push_str(text)
+                    }
+                    Entity::Ansi(_) | Entity::Control(_) => (),
+                }
+            }
+            return content;
+        }
+        // Adjacent codes are combined into a single sequence, e.g. `\x1b[1;31m`.
+        let mut params: Vec<String> = Vec::new();
+        for entity in entities {
+            match entity {
+                Entity::Ansi(Ansi::Reset) => {
+                    flush_params(&mut content, &mut params);
+                    content.push_str(&Ansi::Reset.to_string());
+                }
+                Entity::Ansi(ansi) if level == ColorLevel::Ansi256 => {
+                    params.push(ansi.to_256().params())
+                }
+                // Terminals limited to 16 colors have no underline colors.
+                Entity::Ansi(
+                    Ansi::UnderlineColor256(_)
+                    | Ansi::UnderlineColorRgb(..)
+                    | Ansi::UnderlineColorDefault,
+                ) if level < ColorLevel::Ansi256 => (),
+                Entity::Ansi(ansi) if level < ColorLevel::Ansi256 => {
+                    params.push(ansi.to_basic().params())
+                }
+                Entity::Ansi(ansi) => params.push(ansi.params()),
+                Entity::Text(text) => {
+                    flush_params(&mut content, &mut params);
+                    content.push_str(text);
+                }
+                Entity::Link { text, url } => {
+                    flush_params(&mut content, &mut params);
+                    content.push_str(&format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text));
+                }
+                Entity::Control(text) | Entity::Raw(text) => {
+                    flush_params(&mut content, &mut params);
+                    content.push_str(text);
+                }
+            }
+        }
+        flush_params(&mut content, &mut params);
+        // Only close codes that are still active after the last reset.
+        let open = entities
+            .iter()
+            .rev()
+            .take_while(|entity| **entity != Entity::Ansi(Ansi::Reset))
+            .any(|entity| matches!(entity, Entity::Ansi(_)));
+        if open {
+            content.push_str(&Ansi::Reset.to_string());
+        }
+        content
+    }
+
+    /// Returns the raw content of the `Builder`.
+    /// This is useful for debugging purposes.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
-    /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_white();
+    /// builder.text("Hello, world!");
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgWhite));
     /// ```
-    pub fn fg_white(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgWhite));
-        self
+    pub fn content_raw(&self) -> Vec<Entity> {
+        self.content.to_vec()
     }
 
-    /// Appends a high intensity black foreground ANSI code.
-    /// This will set the text color to high intensity black.
+    /// Appends an ANSI code to the builder.
+    /// This is useful for custom ANSI codes.
     ///
     /// # Examples
     ///
     /// ```
     /// use rcolors::builder::Builder;
-    /// use rcolors::builder::Entity;
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_black();
+    /// builder.ansi(Ansi::BgBlue);
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiBlack));
-    /// ```
-    pub fn fg_hi_black(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiBlack));
+    pub fn ansi(&mut self, ansi: Ansi) -> &mut Self {
+        self.content.push(Entity::Ansi(ansi));
         self
     }
 
-    /// Appends a high intensity red foreground ANSI code.
-    /// This will set the text color to high intensity red.
+    /// Appends a reset ANSI code.
+    /// This will reset all styles and colors.
     ///
     /// # Examples
     ///
@@ -576,17 +809,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_red();
+    /// builder.reset();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiRed));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Reset));
     /// ```
-    pub fn fg_hi_red(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiRed));
+    pub fn reset(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Reset));
         self
     }
 
-    /// Appends a high intensity green foreground ANSI code.
-    /// This will set the text color to high intensity green.
+    /// Appends a bold ANSI code.
+    /// This will make the text bold.
     ///
     /// # Examples
     ///
@@ -596,17 +829,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_green();
+    /// builder.bold();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiGreen));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Bold));
     /// ```
-    pub fn fg_hi_green(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiGreen));
+    pub fn bold(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Bold));
         self
     }
 
-    /// Appends a high intensity yellow foreground ANSI code.
-    /// This will set the text color to high intensity yellow.
+    /// Appends a faint ANSI code.
+    /// This will make the text faint, also known as dim, see `dim`.
     ///
     /// # Examples
     ///
@@ -616,17 +849,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_yellow();
+    /// builder.faint();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiYellow));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Faint));
     /// ```
-    pub fn fg_hi_yellow(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiYellow));
+    pub fn faint(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Faint));
         self
     }
 
-    /// Appends a high intensity blue foreground ANSI code.
-    /// This will set the text color to high intensity blue.
+    /// Appends a faint ANSI code.
+    /// This is an alias for `faint`, as SGR 2 is often called dim.
     ///
     /// # Examples
     ///
@@ -636,17 +869,16 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_blue();
+    /// builder.dim();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiBlue));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Faint));
     /// ```
-    pub fn fg_hi_blue(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiBlue));
-        self
+    pub fn dim(&mut self) -> &mut Self {
+        self.faint()
     }
 
-    /// Appends a high intensity magenta foreground ANSI code.
-    /// This will set the text color to high intensity magenta.
+    /// Appends a italic ANSI code.
+    /// This will make the text faint.
     ///
     /// # Examples
     ///
@@ -656,17 +888,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_magenta();
+    /// builder.italic();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiMagenta));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Italic));
     /// ```
-    pub fn fg_hi_magenta(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiMagenta));
+    pub fn italic(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Italic));
         self
     }
 
-    /// Appends a high intensity cyan foreground ANSI code.
-    /// This will set the text color to high intensity cyan.
+    /// Appends a underline ANSI code.
+    /// This will make the text underlined.
     ///
     /// # Examples
     ///
@@ -676,17 +908,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_cyan();
+    /// builder.underline();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiCyan));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Underline));
     /// ```
-    pub fn fg_hi_cyan(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiCyan));
+    pub fn underline(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Underline));
         self
     }
 
-    /// Appends a high intensity white foreground ANSI code.
-    /// This will set the text color to high intensity white.
+    /// Appends a slow blink ANSI code.
+    /// This will make the text blink slowly until `blink_off` or a reset.
     ///
     /// # Examples
     ///
@@ -696,17 +928,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.fg_hi_white();
+    /// builder.blink_slow();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiWhite));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BlinkSlow));
     /// ```
-    pub fn fg_hi_white(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::FgHiWhite));
+    pub fn blink_slow(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BlinkSlow));
         self
     }
 
-    /// Appends a black background ANSI code.
-    /// This will set the background color to black.
+    /// Appends a rapid blink ANSI code.
+    /// This will make the text blink rapidly until `blink_off` or a reset.
     ///
     /// # Examples
     ///
@@ -716,17 +948,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_black();
+    /// builder.blink_rapid();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgBlack));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BlinkRapid));
     /// ```
-    pub fn bg_black(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgBlack));
+    pub fn blink_rapid(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BlinkRapid));
         self
     }
 
-    /// Appends a red background ANSI code.
-    /// This will set the background color to red.
+    /// Appends a reverse video ANSI code.
+    /// This will reverse the text and background colors.
     ///
     /// # Examples
     ///
@@ -736,17 +968,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_red();
+    /// builder.reverse_video();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgRed));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::ReverseVideo));
     /// ```
-    pub fn bg_red(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgRed));
+    pub fn reverse_video(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::ReverseVideo));
         self
     }
 
-    /// Appends a green background ANSI code.
-    /// This will set the background color to green.
+    /// Appends a reverse video ANSI code.
+    /// This is an alias for `reverse_video` that swaps the text and background colors.
     ///
     /// # Examples
     ///
@@ -756,17 +988,16 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_green();
+    /// builder.invert();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgGreen));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::ReverseVideo));
     /// ```
-    pub fn bg_green(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgGreen));
-        self
+    pub fn invert(&mut self) -> &mut Self {
+        self.reverse_video()
     }
 
-    /// Appends a yellow background ANSI code.
-    /// This will set the background color to yellow.
+    /// Appends a concealed ANSI code.
+    /// This will hide the text.
     ///
     /// # Examples
     ///
@@ -776,17 +1007,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_yellow();
+    /// builder.concealed();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgYellow));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Concealed));
     /// ```
-    pub fn bg_yellow(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgYellow));
+    pub fn concealed(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Concealed));
         self
     }
 
-    /// Appends a blue background ANSI code.
-    /// This will set the background color to blue.
+    /// Appends a crossed out ANSI code.
+    /// This will cross out the text.
     ///
     /// # Examples
     ///
@@ -796,17 +1027,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_blue();
+    /// builder.crossed_out();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgBlue));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::CrossedOut));
     /// ```
-    pub fn bg_blue(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgBlue));
+    pub fn crossed_out(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::CrossedOut));
         self
     }
 
-    /// Appends a magenta background ANSI code.
-    /// This will set the background color to magenta.
+    /// Appends a double underline ANSI code.
+    /// This will make the text double underlined.
     ///
     /// # Examples
     ///
@@ -816,17 +1047,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_magenta();
+    /// builder.double_underline();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgMagenta));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::DoubleUnderline));
     /// ```
-    pub fn bg_magenta(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgMagenta));
+    pub fn double_underline(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::DoubleUnderline));
         self
     }
 
-    /// Appends a cyan background ANSI code.
-    /// This will set the background color to cyan.
+    /// Appends a overline ANSI code.
+    /// This will draw a line above the text.
     ///
     /// # Examples
     ///
@@ -836,17 +1067,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_cyan();
+    /// builder.overline();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgCyan));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Overline));
     /// ```
-    pub fn bg_cyan(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgCyan));
+    pub fn overline(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Overline));
         self
     }
 
-    /// Appends a white background ANSI code.
-    /// This will set the background color to white.
+    /// Appends a bold off ANSI code.
+    /// This will turn off bold and faint text.
     ///
     /// # Examples
     ///
@@ -856,17 +1087,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_white();
+    /// builder.not_bold();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgWhite));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BoldOff));
     /// ```
-    pub fn bg_white(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgWhite));
+    pub fn not_bold(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BoldOff));
         self
     }
 
-    /// Appends a high intensity black background ANSI code.
-    /// This will set the background color to high intensity black.
+    /// Appends a faint off ANSI code.
+    /// This will turn off faint and bold text.
     ///
     /// # Examples
     ///
@@ -876,17 +1107,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_black();
+    /// builder.not_faint();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiBlack));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FaintOff));
     /// ```
-    pub fn bg_hi_black(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiBlack));
+    pub fn not_faint(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FaintOff));
         self
     }
 
-    /// Appends a high intensity red background ANSI code.
-    /// This will set the background color to high intensity red.
+    /// Appends a italic off ANSI code.
+    /// This will turn off italic text.
     ///
     /// # Examples
     ///
@@ -896,17 +1127,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_red();
+    /// builder.not_italic();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiRed));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::ItalicOff));
     /// ```
-    pub fn bg_hi_red(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiRed));
+    pub fn not_italic(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::ItalicOff));
         self
     }
 
-    /// Appends a high intensity green background ANSI code.
-    /// This will set the background color to high intensity green.
+    /// Appends a underline off ANSI code.
+    /// This will turn off underlined text.
     ///
     /// # Examples
     ///
@@ -916,17 +1147,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_green();
+    /// builder.not_underline();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiGreen));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::UnderlineOff));
     /// ```
-    pub fn bg_hi_green(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiGreen));
+    pub fn not_underline(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::UnderlineOff));
         self
     }
 
-    /// Appends a high intensity yellow background ANSI code.
-    /// This will set the background color to high intensity yellow.
+    /// Appends a blink off ANSI code.
+    /// This will turn off both slow and rapid blinking text.
     ///
     /// # Examples
     ///
@@ -936,17 +1167,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_yellow();
+    /// builder.not_blink();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiYellow));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BlinkOff));
     /// ```
-    pub fn bg_hi_yellow(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiYellow));
+    pub fn not_blink(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BlinkOff));
         self
     }
 
-    /// Appends a high intensity blue background ANSI code.
-    /// This will set the background color to high intensity blue.
+    /// Appends a blink off ANSI code.
+    /// This is an alias for `not_blink`, SGR 25 cancels both `blink_slow` and `blink_rapid`.
     ///
     /// # Examples
     ///
@@ -956,17 +1187,16 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_blue();
+    /// builder.blink_off();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiBlue));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BlinkOff));
     /// ```
-    pub fn bg_hi_blue(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiBlue));
-        self
+    pub fn blink_off(&mut self) -> &mut Self {
+        self.not_blink()
     }
 
-    /// Appends a high intensity magenta background ANSI code.
-    /// This will set the background color to high intensity magenta.
+    /// Appends a reverse video off ANSI code.
+    /// This will turn off reversed colors.
     ///
     /// # Examples
     ///
@@ -976,17 +1206,17 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_magenta();
+    /// builder.not_reverse();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiMagenta));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::ReverseOff));
     /// ```
-    pub fn bg_hi_magenta(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiMagenta));
+    pub fn not_reverse(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::ReverseOff));
         self
     }
 
-    /// Appends a high intensity cyan background ANSI code.
-    /// This will set the background color to high intensity cyan.
+    /// Appends a reverse video off ANSI code.
+    /// This is an alias for `not_reverse` that undoes `invert`.
     ///
     /// # Examples
     ///
@@ -996,17 +1226,16 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_cyan();
+    /// builder.no_invert();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiCyan));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::ReverseOff));
     /// ```
-    pub fn bg_hi_cyan(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiCyan));
-        self
+    pub fn no_invert(&mut self) -> &mut Self {
+        self.not_reverse()
     }
 
-    /// Appends a high intensity white background ANSI code.
-    /// This will set the background color to high intensity white.
+    /// Appends a concealed off ANSI code.
+    /// This will reveal concealed text.
     ///
     /// # Examples
     ///
@@ -1016,152 +1245,4352 @@ impl Builder {
     /// use rcolors::ansi::Ansi;
     ///
     /// let mut builder = Builder::new();
-    /// builder.bg_hi_white();
+    /// builder.not_concealed();
     /// assert_eq!(builder.content_raw().len(), 1);
-    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiWhite));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::ConcealedOff));
     /// ```
-    pub fn bg_hi_white(&mut self) -> &mut Self {
-        self.content.push(Entity::Ansi(Ansi::BgHiWhite));
+    pub fn not_concealed(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::ConcealedOff));
         self
     }
-}
 
-#[cfg(test)]
-mod builder_tests {
-    use std::io::Write;
-
-    use super::*;
-    use crate::ansi::Ansi;
+    /// Appends a crossed out off ANSI code.
+    /// This will turn off crossed out text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.not_crossed_out();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::CrossedOutOff));
+    /// ```
+    pub fn not_crossed_out(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::CrossedOutOff));
+        self
+    }
 
-    #[test]
-    fn builder_new() {
-        let builder = Builder::new();
-        assert_eq!(builder.content.len(), 0);
-        assert_eq!(builder.no_color, utils::no_color());
+    /// Appends a black foreground ANSI code.
+    /// This will set the text color to black.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_black();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgBlack));
+    /// ```
+    pub fn fg_black(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgBlack));
+        self
     }
 
-    #[test]
-    fn builder_text() {
-        let mut builder = Builder::new();
-        builder.text("Hello, world!");
-        match &builder.content[0] {
-            Entity::Text(text) => assert_eq!(text, "Hello, world!"),
-            _ => panic!("Expected text entity"),
-        }
+    /// Appends a red foreground ANSI code.
+    /// This will set the text color to red.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_red();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgRed));
+    /// ```
+    pub fn fg_red(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgRed));
+        self
     }
 
-    #[test]
-    fn builder_ansi() {
-        let mut builder = Builder::new();
-        builder.bold().italic();
-        assert_eq!(builder.content.len(), 2);
-        match &builder.content[0] {
-            Entity::Ansi(Ansi::Bold) => (),
-            _ => panic!("Expected Bold ANSI code"),
-        }
-        match &builder.content[1] {
-            Entity::Ansi(Ansi::Italic) => (),
-            _ => panic!("Expected Italic ANSI code"),
-        }
+    /// Appends a green foreground ANSI code.
+    /// This will set the text color to green.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_green();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgGreen));
+    /// ```
+    pub fn fg_green(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgGreen));
+        self
     }
 
-    #[test]
-    fn display_with_color() {
-        let mut builder = Builder::new();
-        builder.force_color = true;
-        builder.text("Hello, ").bold().text("world!").reset();
-        assert_eq!(format!("{}", builder), "Hello, \x1b[1mworld!\x1b[0m");
+    /// Appends a yellow foreground ANSI code.
+    /// This will set the text color to yellow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_yellow();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgYellow));
+    /// ```
+    pub fn fg_yellow(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgYellow));
+        self
     }
 
-    #[test]
-    fn display_without_color() {
-        let mut builder = Builder::new();
-        builder.no_color = true;
-        builder.text("Hello, ").bold().text("world!").reset();
-        assert_eq!(format!("{}", builder), "Hello, world!");
+    /// Appends a blue foreground ANSI code.
+    /// This will set the text color to blue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_blue();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgBlue));
+    /// ```
+    pub fn fg_blue(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgBlue));
+        self
     }
 
-    #[test]
-    fn builder_reset() {
-        let mut builder = Builder::new();
-        builder.reset();
-        assert_eq!(builder.content.len(), 1);
-        match &builder.content[0] {
-            Entity::Ansi(Ansi::Reset) => (),
-            _ => panic!("Expected Reset ANSI code"),
-        }
+    /// Appends a magenta foreground ANSI code.
+    /// This will set the text color to magenta.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_magenta();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgMagenta));
+    /// ```
+    pub fn fg_magenta(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgMagenta));
+        self
     }
 
-    #[test]
-    fn builder_fg_red() {
+    /// Appends a cyan foreground ANSI code.
+    /// This will set the text color to cyan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_cyan();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgCyan));
+    /// ```
+    pub fn fg_cyan(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgCyan));
+        self
+    }
+
+    /// Appends a white foreground ANSI code.
+    /// This will set the text color to white.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_white();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgWhite));
+    /// ```
+    pub fn fg_white(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgWhite));
+        self
+    }
+
+    /// Appends a high intensity black foreground ANSI code.
+    /// This will set the text color to high intensity black.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_black();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiBlack));
+    /// ```
+    pub fn fg_hi_black(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiBlack));
+        self
+    }
+
+    /// Appends a high intensity red foreground ANSI code.
+    /// This will set the text color to high intensity red.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_red();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiRed));
+    /// ```
+    pub fn fg_hi_red(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiRed));
+        self
+    }
+
+    /// Appends a high intensity green foreground ANSI code.
+    /// This will set the text color to high intensity green.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_green();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiGreen));
+    /// ```
+    pub fn fg_hi_green(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiGreen));
+        self
+    }
+
+    /// Appends a high intensity yellow foreground ANSI code.
+    /// This will set the text color to high intensity yellow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_yellow();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiYellow));
+    /// ```
+    pub fn fg_hi_yellow(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiYellow));
+        self
+    }
+
+    /// Appends a high intensity blue foreground ANSI code.
+    /// This will set the text color to high intensity blue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_blue();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiBlue));
+    /// ```
+    pub fn fg_hi_blue(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiBlue));
+        self
+    }
+
+    /// Appends a high intensity magenta foreground ANSI code.
+    /// This will set the text color to high intensity magenta.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_magenta();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiMagenta));
+    /// ```
+    pub fn fg_hi_magenta(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiMagenta));
+        self
+    }
+
+    /// Appends a high intensity cyan foreground ANSI code.
+    /// This will set the text color to high intensity cyan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_cyan();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiCyan));
+    /// ```
+    pub fn fg_hi_cyan(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiCyan));
+        self
+    }
+
+    /// Appends a high intensity white foreground ANSI code.
+    /// This will set the text color to high intensity white.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hi_white();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgHiWhite));
+    /// ```
+    pub fn fg_hi_white(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgHiWhite));
+        self
+    }
+
+    /// Appends a default foreground ANSI code.
+    /// This will set the foreground color to the terminal default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_default();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgDefault));
+    /// ```
+    pub fn fg_default(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgDefault));
+        self
+    }
+
+    /// Appends a foreground ANSI code with the given RGB value.
+    /// This requires a terminal with 24-bit color support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_rgb(255, 128, 0);
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgRgb(255, 128, 0)));
+    /// ```
+    pub fn fg_rgb(&mut self, r: u8, g: u8, b: u8) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::FgRgb(r, g, b)));
+        self
+    }
+
+    /// Appends a foreground ANSI code from the 256 color palette.
+    /// This requires a terminal with 256 color support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_256(208);
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Fg256(208)));
+    /// ```
+    pub fn fg_256(&mut self, n: u8) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Fg256(n)));
+        self
+    }
+
+    /// Appends a foreground ANSI code parsed from a hex color.
+    /// Accepts `#rrggbb`, `rrggbb` and the shorthand `#rgb`, see `Ansi::from_hex`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hex("#ff8800").unwrap().text("orange");
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgRgb(255, 136, 0)));
+    /// assert!(builder.fg_hex("#ff88").is_err());
+    /// ```
+    pub fn fg_hex(&mut self, hex: &str) -> Result<&mut Self, ParseColorError> {
+        let ansi = Ansi::from_hex(hex)?;
+        Ok(self.ansi(ansi))
+    }
+
+    /// Appends a foreground ANSI code parsed from a hex color or the fallback if it is invalid.
+    /// This keeps the chain intact when the hex color comes from user input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_hex_or("not a color", Ansi::FgDefault).text("text");
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgDefault));
+    /// ```
+    pub fn fg_hex_or(&mut self, hex: &str, fallback: Ansi) -> &mut Self {
+        self.ansi(Ansi::from_hex(hex).unwrap_or(fallback))
+    }
+
+    /// Appends a black background ANSI code.
+    /// This will set the background color to black.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_black();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgBlack));
+    /// ```
+    pub fn bg_black(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgBlack));
+        self
+    }
+
+    /// Appends a red background ANSI code.
+    /// This will set the background color to red.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_red();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgRed));
+    /// ```
+    pub fn bg_red(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgRed));
+        self
+    }
+
+    /// Appends a green background ANSI code.
+    /// This will set the background color to green.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_green();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgGreen));
+    /// ```
+    pub fn bg_green(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgGreen));
+        self
+    }
+
+    /// Appends a yellow background ANSI code.
+    /// This will set the background color to yellow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_yellow();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgYellow));
+    /// ```
+    pub fn bg_yellow(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgYellow));
+        self
+    }
+
+    /// Appends a blue background ANSI code.
+    /// This will set the background color to blue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_blue();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgBlue));
+    /// ```
+    pub fn bg_blue(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgBlue));
+        self
+    }
+
+    /// Appends a magenta background ANSI code.
+    /// This will set the background color to magenta.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_magenta();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgMagenta));
+    /// ```
+    pub fn bg_magenta(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgMagenta));
+        self
+    }
+
+    /// Appends a cyan background ANSI code.
+    /// This will set the background color to cyan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_cyan();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgCyan));
+    /// ```
+    pub fn bg_cyan(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgCyan));
+        self
+    }
+
+    /// Appends a white background ANSI code.
+    /// This will set the background color to white.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_white();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgWhite));
+    /// ```
+    pub fn bg_white(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgWhite));
+        self
+    }
+
+    /// Appends a high intensity black background ANSI code.
+    /// This will set the background color to high intensity black.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_black();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiBlack));
+    /// ```
+    pub fn bg_hi_black(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiBlack));
+        self
+    }
+
+    /// Appends a high intensity red background ANSI code.
+    /// This will set the background color to high intensity red.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_red();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiRed));
+    /// ```
+    pub fn bg_hi_red(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiRed));
+        self
+    }
+
+    /// Appends a high intensity green background ANSI code.
+    /// This will set the background color to high intensity green.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_green();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiGreen));
+    /// ```
+    pub fn bg_hi_green(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiGreen));
+        self
+    }
+
+    /// Appends a high intensity yellow background ANSI code.
+    /// This will set the background color to high intensity yellow.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_yellow();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiYellow));
+    /// ```
+    pub fn bg_hi_yellow(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiYellow));
+        self
+    }
+
+    /// Appends a high intensity blue background ANSI code.
+    /// This will set the background color to high intensity blue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_blue();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiBlue));
+    /// ```
+    pub fn bg_hi_blue(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiBlue));
+        self
+    }
+
+    /// Appends a high intensity magenta background ANSI code.
+    /// This will set the background color to high intensity magenta.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_magenta();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiMagenta));
+    /// ```
+    pub fn bg_hi_magenta(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiMagenta));
+        self
+    }
+
+    /// Appends a high intensity cyan background ANSI code.
+    /// This will set the background color to high intensity cyan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_cyan();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiCyan));
+    /// ```
+    pub fn bg_hi_cyan(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiCyan));
+        self
+    }
+
+    /// Appends a high intensity white background ANSI code.
+    /// This will set the background color to high intensity white.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hi_white();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgHiWhite));
+    /// ```
+    pub fn bg_hi_white(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgHiWhite));
+        self
+    }
+
+    /// Appends a default background ANSI code.
+    /// This will set the background color to the terminal default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_default();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgDefault));
+    /// ```
+    pub fn bg_default(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgDefault));
+        self
+    }
+
+    /// Appends a background ANSI code with the given RGB value.
+    /// This requires a terminal with 24-bit color support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_rgb(255, 128, 0);
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgRgb(255, 128, 0)));
+    /// ```
+    pub fn bg_rgb(&mut self, r: u8, g: u8, b: u8) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::BgRgb(r, g, b)));
+        self
+    }
+
+    /// Appends a background ANSI code from the 256 color palette.
+    /// This requires a terminal with 256 color support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_256(17);
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Bg256(17)));
+    /// ```
+    pub fn bg_256(&mut self, n: u8) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::Bg256(n)));
+        self
+    }
+
+    /// Appends a background ANSI code parsed from a hex color.
+    /// Accepts `#rrggbb`, `rrggbb` and the shorthand `#rgb`, see `Ansi::from_hex_bg`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hex("#ff8800").unwrap().text("orange");
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgRgb(255, 136, 0)));
+    /// assert!(builder.bg_hex("#ff88").is_err());
+    /// ```
+    pub fn bg_hex(&mut self, hex: &str) -> Result<&mut Self, ParseColorError> {
+        let ansi = Ansi::from_hex_bg(hex)?;
+        Ok(self.ansi(ansi))
+    }
+
+    /// Appends a background ANSI code parsed from a hex color or the fallback if it is invalid.
+    /// This keeps the chain intact when the hex color comes from user input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bg_hex_or("not a color", Ansi::BgDefault).text("text");
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::BgDefault));
+    /// ```
+    pub fn bg_hex_or(&mut self, hex: &str, fallback: Ansi) -> &mut Self {
+        self.ansi(Ansi::from_hex_bg(hex).unwrap_or(fallback))
+    }
+
+    /// Appends an underline color ANSI code with the given RGB value.
+    /// Not all terminals support colored underlines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.underline_color_rgb(255, 0, 0);
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::UnderlineColorRgb(255, 0, 0)));
+    /// ```
+    pub fn underline_color_rgb(&mut self, r: u8, g: u8, b: u8) -> &mut Self {
+        self.content
+            .push(Entity::Ansi(Ansi::UnderlineColorRgb(r, g, b)));
+        self
+    }
+
+    /// Appends an underline color ANSI code from the 256 color palette.
+    /// Not all terminals support colored underlines.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.underline_color_256(208);
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::UnderlineColor256(208)));
+    /// ```
+    pub fn underline_color_256(&mut self, n: u8) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::UnderlineColor256(n)));
+        self
+    }
+
+    /// Appends a default underline color ANSI code.
+    /// This will set the underline color back to the text color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.underline_color_default();
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::UnderlineColorDefault));
+    /// ```
+    pub fn underline_color_default(&mut self) -> &mut Self {
+        self.content.push(Entity::Ansi(Ansi::UnderlineColorDefault));
+        self
+    }
+
+    /// Pads the builder with blank lines up to the given height.
+    /// This will append newlines until the content spans `lines` lines.
+    /// Content that is already taller is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("first\nsecond").pad_to_height(4);
+    /// assert_eq!(builder.content_raw()[1], Entity::Text("\n\n".to_string()));
+    /// ```
+    pub fn pad_to_height(&mut self, lines: usize) -> &mut Self {
+        let height = self.line_count();
+        if height < lines {
+            self.content.push(Entity::Text("\n".repeat(lines - height)));
+        }
+        self
+    }
+
+    // Returns the number of lines spanned by the text content.
+    fn line_count(&self) -> usize {
+        let newlines: usize = self
+            .content
+            .iter()
+            .map(|entity| match entity {
+                Entity::Text(text) | Entity::Link { text, .. } => text.matches('\n').count(),
+                Entity::Ansi(_) | Entity::Control(_) | Entity::Raw(_) => 0,
+            })
+            .sum();
+        newlines + 1
+    }
+
+    /// Returns a colored diff between the entities of two builders.
+    /// Entities only present in `other` are marked green with `+`,
+    /// entities only present in `self` are marked red with `-` and listed first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut expected = Builder::new();
+    /// expected.fg_red().text("error");
+    /// let mut actual = Builder::new();
+    /// actual.fg_yellow().text("error");
+    /// let diff = expected.diff_against(&actual);
+    /// assert!(diff.contains("- Ansi(FgRed)"));
+    /// assert!(diff.contains("+ Ansi(FgYellow)"));
+    /// ```
+    pub fn diff_against(&self, other: &Builder) -> String {
+        let mut diff = String::new();
+        for change in diff_lcs(&self.content, &other.content) {
+            match change {
+                Change::Same(entity) => diff.push_str(&format!("  {:?}", entity)),
+                Change::Added(entity) => {
+                    diff.push_str(&color_sprint!(Ansi::FgGreen, format!("+ {:?}", entity)))
+                }
+                Change::Removed(entity) => {
+                    diff.push_str(&color_sprint!(Ansi::FgRed, format!("- {:?}", entity)))
+                }
+            }
+            diff.push('\n');
+        }
+        diff
+    }
+
+    /// Creates a new `Builder` with a line-based diff between two strings.
+    /// Removed lines are red with `-`, added lines are green with `+`
+    /// and unchanged lines are indented by two spaces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut diff = Builder::diff("a\nb", "a\nc");
+    /// diff.force_color();
+    /// assert_eq!(
+    ///     diff.as_string(),
+    ///     "  a\n\x1b[31m- b\x1b[0m\n\x1b[32m+ c\x1b[0m\n"
+    /// );
+    /// ```
+    pub fn diff(old: &str, new: &str) -> Self {
+        let old: Vec<&str> = old.lines().collect();
+        let new: Vec<&str> = new.lines().collect();
+        let mut builder = Self::new();
+        for change in diff_lcs(&old, &new) {
+            match change {
+                Change::Same(line) => builder.text(&format!("  {}", line)),
+                Change::Removed(line) => builder.fg_red().text(&format!("- {}", line)).reset(),
+                Change::Added(line) => builder.fg_green().text(&format!("+ {}", line)).reset(),
+            };
+            builder.newline();
+        }
+        builder
+    }
+
+    /// Returns a new `Builder` containing only the lines `[start, start + count)`.
+    /// Styles that are still active from earlier lines are re-applied
+    /// at the beginning of the first retained line.
+    /// Lines out of range are skipped, so fewer lines may be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_red().text("one\ntwo\nthree");
+    /// let visible = builder.visible_lines(1, 1);
+    /// assert_eq!(
+    ///     visible.content_raw(),
+    ///     vec![Entity::Ansi(Ansi::FgRed), Entity::Text("two".to_string())]
+    /// );
+    /// ```
+    pub fn visible_lines(&self, start: usize, count: usize) -> Builder {
+        // Only the settings are copied, the content is built below.
+        let mut builder = Builder {
+            content: Content::default(),
+            no_color: self.no_color,
+            force_color: self.force_color,
+            stream: self.stream,
+            auto_downsample: self.auto_downsample,
+            partial_utf8: Vec::new(),
+        };
+        let end = start.saturating_add(count);
+        if count == 0 {
+            return builder;
+        }
+
+        let mut line = 0;
+        let mut active: Vec<Ansi> = Vec::new();
+        for entity in &self.content {
+            if line >= end {
+                break;
+            }
+            match entity {
+                Entity::Ansi(ansi) => {
+                    if line >= start {
+                        builder.content.push(Entity::Ansi(*ansi));
+                    }
+                    match ansi {
+                        Ansi::Reset => active.clear(),
+                        _ => active.push(*ansi),
+                    }
+                }
+                Entity::Text(text) => {
+                    for piece in text.split_inclusive('\n') {
+                        if line >= end {
+                            break;
+                        }
+                        let newline = piece.ends_with('\n');
+                        if line >= start {
+                            let piece = if newline && line + 1 == end {
+                                &piece[..piece.len() - 1]
+                            } else {
+                                piece
+                            };
+                            if !piece.is_empty() {
+                                builder.content.push(Entity::Text(piece.to_string()));
+                            }
+                        }
+                        if newline {
+                            line += 1;
+                            if line == start {
+                                builder
+                                    .content
+                                    .extend(active.iter().map(|a| Entity::Ansi(*a)));
+                            }
+                        }
+                    }
+                }
+                Entity::Link { .. } | Entity::Control(_) | Entity::Raw(_) => {
+                    if line >= start {
+                        builder.content.push(entity.clone());
+                    }
+                }
+            }
+        }
+        builder
+    }
+
+    /// Returns the number of visible characters of the builder.
+    /// ANSI codes are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("Hello, ").fg_red().text("world!").reset();
+    /// assert_eq!(builder.visible_len(), 13);
+    /// ```
+    pub fn visible_len(&self) -> usize {
+        self.content
+            .iter()
+            .map(|entity| match entity {
+                Entity::Text(text) | Entity::Link { text, .. } => text.chars().count(),
+                Entity::Raw(text) => utils::visible_len(text),
+                Entity::Ansi(_) | Entity::Control(_) => 0,
+            })
+            .sum()
+    }
+
+    /// Writes the content of the `Builder` to the given writer.
+    /// ANSI codes are left out if colors are disabled, like in `as_string`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("Hello, world!").bold().text(" This is bold text!").reset();
+    /// let mut buffer = Vec::new();
+    /// builder.write_to(&mut buffer).unwrap();
+    /// assert_eq!(buffer, builder.as_string().into_bytes());
+    /// ```
+    pub fn write_to<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(self.as_string().as_bytes())
+    }
+
+    /// Appends text with a color gradient.
+    /// Each character gets its own RGB foreground color,
+    /// interpolated linearly from `start` to `end`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.gradient("abc", (255, 0, 0), (0, 0, 255)).reset();
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgRgb(255, 0, 0)));
+    /// assert_eq!(builder.content_raw()[4], Entity::Ansi(Ansi::FgRgb(0, 0, 255)));
+    /// ```
+    pub fn gradient(&mut self, text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> &mut Self {
+        let count = text.chars().count();
+        for (i, c) in text.chars().enumerate() {
+            let t = if count > 1 {
+                i as f64 / (count - 1) as f64
+            } else {
+                0.0
+            };
+            let (r, g, b) = utils::lerp_rgb(start, end, t);
+            self.content.push(Entity::Ansi(Ansi::FgRgb(r, g, b)));
+            self.content.push(Entity::Text(c.to_string()));
+        }
+        self
+    }
+
+    /// Appends text in rainbow colors.
+    /// The hue is cycled once across all characters and the foreground is reset afterwards.
+    /// Whitespace gets no color but still advances the hue to keep the colors aligned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.rainbow("Rust").reset();
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgRgb(255, 0, 0)));
+    /// ```
+    pub fn rainbow(&mut self, text: &str) -> &mut Self {
+        let count = text.chars().count();
+        for (i, c) in text.chars().enumerate() {
+            if !c.is_whitespace() {
+                let (r, g, b) = hsv_to_rgb(360.0 * i as f64 / count as f64, 1.0, 1.0);
+                self.content.push(Entity::Ansi(Ansi::FgRgb(r, g, b)));
+            }
+            self.content.push(Entity::Text(c.to_string()));
+        }
+        if text.chars().any(|c| !c.is_whitespace()) {
+            self.content.push(Entity::Ansi(Ansi::FgDefault));
+        }
+        self
+    }
+
+    /// Removes all content from the builder.
+    /// The color settings are kept, so the builder can be reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("Hello, world!").clear();
+    /// assert!(builder.content_raw().is_empty());
+    /// ```
+    pub fn clear(&mut self) -> &mut Self {
+        self.content.clear();
+        self
+    }
+
+    /// Returns the number of entities in the builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("Hello, world!");
+    /// assert_eq!(builder.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.content.len()
+    }
+
+    /// Returns `true` if the builder contains no entities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let builder = Builder::new();
+    /// assert!(builder.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+    }
+
+    /// Returns the total length in bytes of all text entities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("Hello, ").reset().text("world!");
+    /// assert_eq!(builder.text_len(), 13);
+    /// ```
+    pub fn text_len(&self) -> usize {
+        self.content
+            .iter()
+            .map(|entity| match entity {
+                Entity::Text(text) | Entity::Link { text, .. } | Entity::Raw(text) => text.len(),
+                Entity::Ansi(_) | Entity::Control(_) => 0,
+            })
+            .sum()
+    }
+
+    /// Appends the entities of another builder.
+    /// The color settings of `self` are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut other = Builder::new();
+    /// other.bold().text("world!").reset();
+    /// let mut builder = Builder::new();
+    /// builder.text("Hello, ").extend_from(&other);
+    /// assert_eq!(builder.content_raw().len(), 4);
+    /// ```
+    pub fn extend_from(&mut self, other: &Builder) -> &mut Self {
+        self.content.extend_from_slice(&other.content);
+        self
+    }
+
+    /// Returns a reference to the entity at the given index.
+    /// Returns `None` if the index is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("Hello, world!");
+    /// assert_eq!(builder.get(0), Some(&Entity::Ansi(Ansi::Bold)));
+    /// assert_eq!(builder.get(2), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&Entity> {
+        self.content.get(index)
+    }
+
+    /// Returns a reference to the last entity.
+    /// Returns `None` if the builder is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("Hello, world!");
+    /// assert_eq!(builder.last(), Some(&Entity::Text("Hello, world!".to_string())));
+    /// ```
+    pub fn last(&self) -> Option<&Entity> {
+        self.content.last()
+    }
+
+    /// Removes the last entity and returns it.
+    /// Returns `None` if the builder is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("Hello, world!").reset();
+    /// assert_eq!(builder.pop(), Some(Entity::Ansi(Ansi::Reset)));
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// ```
+    pub fn pop(&mut self) -> Option<Entity> {
+        self.content.pop()
+    }
+
+    /// Applies the closure to the builder if the condition is `true`.
+    /// This keeps conditional styling inside a chain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let verbose = true;
+    /// let mut builder = Builder::new();
+    /// builder.when(verbose, |b| {
+    ///     b.faint();
+    /// }).text("details");
+    /// assert_eq!(builder.content_raw().len(), 2);
+    /// ```
+    pub fn when(&mut self, cond: bool, f: impl FnOnce(&mut Self)) -> &mut Self {
+        if cond {
+            f(self);
+        }
+        self
+    }
+
+    /// Appends a hyperlink using the OSC 8 escape sequence.
+    /// Terminals with support render `text` as a clickable link to `url`.
+    /// If colors are disabled only the text is emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.force_color().link("rcolors", "https://crates.io/crates/rcolors");
+    /// assert_eq!(
+    ///     builder.as_string(),
+    ///     "\x1b]8;;https://crates.io/crates/rcolors\x1b\\rcolors\x1b]8;;\x1b\\"
+    /// );
+    /// ```
+    pub fn link(&mut self, text: &str, url: &str) -> &mut Self {
+        self.content.push(Entity::Link {
+            text: text.to_string(),
+            url: url.to_string(),
+        });
+        self
+    }
+
+    /// Enables or disables downsampling of colors in `as_string`.
+    /// If enabled, RGB and 256 colors are converted to the best
+    /// representation supported by the terminal, see `utils::color_level`.
+    /// The print methods always downsample.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.auto_downsample(true).ansi(rcolors::ansi::Ansi::FgRgb(255, 0, 0));
+    /// ```
+    pub fn auto_downsample(&mut self, enabled: bool) -> &mut Self {
+        self.auto_downsample = enabled;
+        self
+    }
+
+    /// Appends an entity to the builder.
+    /// This is useful if entities are already at hand, e.g. from deserialization.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.push(Entity::Ansi(Ansi::Bold));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Bold));
+    /// ```
+    pub fn push(&mut self, entity: Entity) -> &mut Self {
+        self.content.push(entity);
+        self
+    }
+
+    /// Returns an iterator over the entities of the builder.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("Hello, world!").reset();
+    /// assert_eq!(builder.iter().count(), 3);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, Entity> {
+        self.content.iter()
+    }
+
+    /// Applies the function to every text entity in place.
+    /// ANSI codes are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("hello").map_text(|text| text.to_uppercase());
+    /// assert_eq!(builder.content_raw()[1], Entity::Text("HELLO".to_string()));
+    /// ```
+    pub fn map_text(&mut self, f: impl Fn(&str) -> String) -> &mut Self {
+        for entity in &mut self.content {
+            if let Entity::Text(text) = entity {
+                *text = f(text);
+            }
+        }
+        self
+    }
+
+    /// Appends the text repeated `count` times as a single entity.
+    /// Nothing is appended if `count` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.repeat_text("-", 5);
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("-----".to_string()));
+    /// ```
+    pub fn repeat_text(&mut self, text: &str, count: usize) -> &mut Self {
+        if count > 0 {
+            self.content.push(Entity::Text(text.repeat(count)));
+        }
+        self
+    }
+
+    /// Appends text with the given style, followed by a reset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    /// use rcolors::builder::Builder;
+    /// use rcolors::style::Style;
+    ///
+    /// let style = Style {
+    ///     fg: Some(Ansi::FgRed),
+    ///     bg: None,
+    ///     attrs: vec![Ansi::Bold],
+    /// };
+    /// let mut builder = Builder::new();
+    /// builder.force_color().style(&style, "error");
+    /// assert_eq!(builder.as_string(), "\x1b[1;31merror\x1b[0m");
+    /// ```
+    pub fn style(&mut self, style: &Style, text: &str) -> &mut Self {
+        self.content
+            .extend(style.codes().into_iter().map(Entity::Ansi));
+        self.content.push(Entity::Text(text.to_string()));
+        self.content.push(Entity::Ansi(Ansi::Reset));
+        self
+    }
+
+    /// Truncates the text so at most `max` characters are visible.
+    /// Text beyond the limit is shortened or dropped while ANSI codes are kept,
+    /// and a reset is appended if the content was truncated.
+    /// Raw text is counted without its escape sequences, like in `visible_len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_red().text("Hello, ").fg_blue().text("world!").truncate_visible(8);
+    /// assert_eq!(builder.visible_len(), 8);
+    /// ```
+    pub fn truncate_visible(&mut self, max: usize) -> &mut Self {
+        let mut remaining = max;
+        let mut truncated = false;
+        self.content.retain_mut(|entity| {
+            let text = match entity {
+                Entity::Text(text) | Entity::Link { text, .. } => text,
+                Entity::Raw(raw) => {
+                    let len = utils::visible_len(raw);
+                    if len <= remaining {
+                        remaining -= len;
+                        return true;
+                    }
+                    truncated = true;
+                    *raw = truncate_raw(raw, remaining);
+                    remaining = 0;
+                    return !raw.is_empty();
+                }
+                Entity::Ansi(_) | Entity::Control(_) => return true,
+            };
+            let len = text.chars().count();
+            if len <= remaining {
+                remaining -= len;
+                return true;
+            }
+            truncated = true;
+            if remaining == 0 {
+                return false;
+            }
+            let end = text
+                .char_indices()
+                .nth(remaining)
+                .map_or(text.len(), |(i, _)| i);
+            text.truncate(end);
+            remaining = 0;
+            true
+        });
+        if truncated && self.content.last() != Some(&Entity::Ansi(Ansi::Reset)) {
+            self.content.push(Entity::Ansi(Ansi::Reset));
+        }
+        self
+    }
+
+    /// Centers the content within the given width by adding spaces on both sides.
+    /// The width is based on the visible length, so ANSI codes are not counted.
+    /// Content that is already wider is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_red().text("abc").reset().center(9);
+    /// assert_eq!(builder.visible_len(), 9);
+    /// ```
+    pub fn center(&mut self, width: usize) -> &mut Self {
+        let padding = width.saturating_sub(self.visible_len());
+        self.pad_left(self.visible_len() + padding / 2);
+        self.pad_right(width)
+    }
+
+    /// Pads the content with spaces on the left up to the given width.
+    /// The width is based on the visible length, so ANSI codes are not counted.
+    /// Content that is already wider is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("42").reset().pad_left(5);
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("   ".to_string()));
+    /// ```
+    pub fn pad_left(&mut self, width: usize) -> &mut Self {
+        let padding = width.saturating_sub(self.visible_len());
+        if padding > 0 {
+            self.content.insert(0, Entity::Text(" ".repeat(padding)));
+        }
+        self
+    }
+
+    /// Pads the content with spaces on the right up to the given width.
+    /// The width is based on the visible length, so ANSI codes are not counted.
+    /// Content that is already wider is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("42").reset().pad_right(5);
+    /// assert_eq!(builder.content_raw()[3], Entity::Text("   ".to_string()));
+    /// ```
+    pub fn pad_right(&mut self, width: usize) -> &mut Self {
+        let padding = width.saturating_sub(self.visible_len());
+        if padding > 0 {
+            self.content.push(Entity::Text(" ".repeat(padding)));
+        }
+        self
+    }
+
+    /// Merges consecutive text entities into one.
+    /// ANSI codes and links stay as boundaries between the merged texts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("a").text("b").reset().text("c").coalesce_text();
+    /// assert_eq!(builder.content_raw(), vec![
+    ///     Entity::Text("ab".to_string()),
+    ///     Entity::Ansi(Ansi::Reset),
+    ///     Entity::Text("c".to_string()),
+    /// ]);
+    /// ```
+    pub fn coalesce_text(&mut self) -> &mut Self {
+        let mut content: Vec<Entity> = Vec::with_capacity(self.content.len());
+        for entity in self.content.drain(..) {
+            match (content.last_mut(), entity) {
+                (Some(Entity::Text(last)), Entity::Text(text)) => last.push_str(&text),
+                (_, entity) => content.push(entity),
+            }
+        }
+        *self.content = content;
+        self
+    }
+
+    /// Returns the content as HTML.
+    /// Styles become `<span style="...">` wrappers and text is HTML-escaped.
+    /// A reset closes the open span, links become `<a>` elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let html = Builder::new().fg_red().text("a < b").reset().to_html();
+    /// assert_eq!(html, "<span style=\"color:#aa0000\">a &lt; b</span>");
+    /// ```
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let mut style = StyleState::default();
+        let mut open: Option<String> = None;
+        for entity in &self.content {
+            let text = match entity {
+                Entity::Ansi(ansi) => {
+                    style.apply(*ansi);
+                    if *ansi == Ansi::Reset && open.take().is_some() {
+                        html.push_str("</span>");
+                    }
+                    continue;
+                }
+                Entity::Control(_) => continue,
+                Entity::Raw(text) => escape_html(&utils::strip_ansi(text)),
+                Entity::Text(text) => escape_html(text),
+                Entity::Link { text, url } => {
+                    format!("<a href=\"{}\">{}</a>", escape_html(url), escape_html(text))
+                }
+            };
+            let css = style.css();
+            if open.as_ref() != Some(&css) {
+                if open.take().is_some() {
+                    html.push_str("</span>");
+                }
+                if !css.is_empty() {
+                    html.push_str(&format!("<span style=\"{}\">", css));
+                    open = Some(css);
+                }
+            }
+            html.push_str(&text);
+        }
+        if open.is_some() {
+            html.push_str("</span>");
+        }
+        html
+    }
+
+    /// Appends text with each word colored by the next color of the palette.
+    /// The palette is cycled and each word is followed by a reset,
+    /// so the whitespace between the words is left uncolored.
+    /// An empty palette appends the text as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.multicolor_words("Hello world", &[Ansi::FgRed, Ansi::FgGreen]);
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgRed));
+    /// assert_eq!(builder.content_raw()[4], Entity::Ansi(Ansi::FgGreen));
+    /// ```
+    pub fn multicolor_words(&mut self, text: &str, palette: &[Ansi]) -> &mut Self {
+        if palette.is_empty() {
+            return self.text(text);
+        }
+        let mut colors = palette.iter().cycle();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            if end > 0 {
+                let color = colors.next().expect("palette is not empty");
+                self.ansi(*color).text(&rest[..end]).reset();
+                rest = &rest[end..];
+            }
+            let end = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            if end > 0 {
+                self.text(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+        self
+    }
+
+    /// Moves the cursor up by `n` lines.
+    /// Like ANSI codes, cursor movement is left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.cursor_up(2);
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[2A".to_string()));
+    /// ```
+    pub fn cursor_up(&mut self, n: u16) -> &mut Self {
+        self.content.push(Entity::Control(format!("\x1b[{}A", n)));
+        self
+    }
+
+    /// Moves the cursor down by `n` lines.
+    /// Like ANSI codes, cursor movement is left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.cursor_down(2);
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[2B".to_string()));
+    /// ```
+    pub fn cursor_down(&mut self, n: u16) -> &mut Self {
+        self.content.push(Entity::Control(format!("\x1b[{}B", n)));
+        self
+    }
+
+    /// Moves the cursor forward by `n` columns.
+    /// Like ANSI codes, cursor movement is left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.cursor_forward(2);
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[2C".to_string()));
+    /// ```
+    pub fn cursor_forward(&mut self, n: u16) -> &mut Self {
+        self.content.push(Entity::Control(format!("\x1b[{}C", n)));
+        self
+    }
+
+    /// Moves the cursor back by `n` columns.
+    /// Like ANSI codes, cursor movement is left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.cursor_back(2);
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[2D".to_string()));
+    /// ```
+    pub fn cursor_back(&mut self, n: u16) -> &mut Self {
+        self.content.push(Entity::Control(format!("\x1b[{}D", n)));
+        self
+    }
+
+    /// Moves the cursor to the given row and column, both starting at 1.
+    /// Like ANSI codes, cursor movement is left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.cursor_to(3, 7);
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[3;7H".to_string()));
+    /// ```
+    pub fn cursor_to(&mut self, row: u16, col: u16) -> &mut Self {
+        self.content
+            .push(Entity::Control(format!("\x1b[{};{}H", row, col)));
+        self
+    }
+
+    /// Clears the entire screen.
+    /// Like ANSI codes, erase codes are left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.clear_screen();
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[2J".to_string()));
+    /// ```
+    pub fn clear_screen(&mut self) -> &mut Self {
+        self.content.push(Entity::Control("\x1b[2J".to_string()));
+        self
+    }
+
+    /// Clears the entire current line.
+    /// Like ANSI codes, erase codes are left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.clear_line();
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[2K".to_string()));
+    /// ```
+    pub fn clear_line(&mut self) -> &mut Self {
+        self.content.push(Entity::Control("\x1b[2K".to_string()));
+        self
+    }
+
+    /// Clears the current line from the cursor to the end of the line.
+    /// Like ANSI codes, erase codes are left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.clear_to_eol();
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b[0K".to_string()));
+    /// ```
+    pub fn clear_to_eol(&mut self) -> &mut Self {
+        self.content.push(Entity::Control("\x1b[0K".to_string()));
+        self
+    }
+
+    /// Sets the title of the terminal window.
+    /// Control characters in the title are removed to keep the sequence intact.
+    /// Like ANSI codes, the title is left out if colors are disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.set_title("build");
+    /// assert_eq!(builder.content_raw()[0], Entity::Control("\x1b]0;build\x07".to_string()));
+    /// ```
+    pub fn set_title(&mut self, title: &str) -> &mut Self {
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        self.content
+            .push(Entity::Control(format!("\x1b]0;{}\x07", title)));
+        self
+    }
+
+    /// Appends the styles, applies the closure and appends a reset.
+    /// This keeps the styling balanced, whatever the closure appends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.scoped(&[Ansi::Bold, Ansi::FgRed], |b| {
+    ///     b.text("error");
+    /// });
+    /// assert_eq!(builder.content_raw().len(), 4);
+    /// assert_eq!(builder.last(), Some(&Entity::Ansi(Ansi::Reset)));
+    /// ```
+    pub fn scoped(&mut self, styles: &[Ansi], f: impl FnOnce(&mut Self)) -> &mut Self {
+        self.content
+            .extend(styles.iter().map(|style| Entity::Ansi(*style)));
+        f(self);
+        self.reset()
+    }
+
+    /// Appends a newline.
+    /// This reads better in a chain than `text("\n")`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("a").newline().text("b");
+    /// assert_eq!(builder.content_raw()[1], Entity::Text("\n".to_string()));
+    /// ```
+    pub fn newline(&mut self) -> &mut Self {
+        self.text("\n")
+    }
+
+    /// Appends `count` newlines as a single text entity.
+    /// Nothing is appended if `count` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.newline_n(3);
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("\n\n\n".to_string()));
+    /// ```
+    pub fn newline_n(&mut self, count: usize) -> &mut Self {
+        if count > 0 {
+            self.text(&"\n".repeat(count));
+        }
+        self
+    }
+
+    /// Appends a tab.
+    /// This reads better in a chain than `text("\t")`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.tab();
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("\t".to_string()));
+    /// ```
+    pub fn tab(&mut self) -> &mut Self {
+        self.text("\t")
+    }
+
+    /// Appends a space.
+    /// This reads better in a chain than `text(" ")`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.space();
+    /// assert_eq!(builder.content_raw()[0], Entity::Text(" ".to_string()));
+    /// ```
+    pub fn space(&mut self) -> &mut Self {
+        self.text(" ")
+    }
+
+    /// Inserts an entity at the given index, shifting all entities after it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of entities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("Hello, world!").insert(0, Entity::Ansi(Ansi::Bold));
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::Bold));
+    /// ```
+    pub fn insert(&mut self, index: usize, entity: Entity) -> &mut Self {
+        self.content.insert(index, entity);
+        self
+    }
+
+    /// Replaces all matches of `from` with `to` in every text entity.
+    /// The replacement is done per entity, so matches spanning an ANSI code
+    /// or multiple text entities are not replaced. Links are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.text("Hello, {name}!").replace("{name}", "world");
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("Hello, world!".to_string()));
+    /// ```
+    pub fn replace(&mut self, from: &str, to: &str) -> &mut Self {
+        self.map_text(|text| text.replace(from, to))
+    }
+
+    /// Replaces tabs in the text with spaces up to the next tab stop.
+    /// The column is counted in visible characters, so ANSI codes don't break the alignment.
+    /// A `tab_width` of 0 removes the tabs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.fg_red().text("ab").reset().text("\tc").expand_tabs(4);
+    /// assert_eq!(builder.content_raw()[3], Entity::Text("  c".to_string()));
+    /// ```
+    pub fn expand_tabs(&mut self, tab_width: usize) -> &mut Self {
+        let mut column = 0;
+        for entity in &mut self.content {
+            match entity {
+                Entity::Text(text) => {
+                    let mut expanded = String::with_capacity(text.len());
+                    for c in text.chars() {
+                        match c {
+                            '\t' if tab_width > 0 => {
+                                let spaces = tab_width - column % tab_width;
+                                expanded.push_str(&" ".repeat(spaces));
+                                column += spaces;
+                            }
+                            '\t' => {}
+                            '\n' => {
+                                expanded.push(c);
+                                column = 0;
+                            }
+                            c => {
+                                expanded.push(c);
+                                column += 1;
+                            }
+                        }
+                    }
+                    *text = expanded;
+                }
+                Entity::Link { text, .. } => column += text.chars().count(),
+                Entity::Raw(text) => column += utils::visible_len(text),
+                Entity::Ansi(_) | Entity::Control(_) => {}
+            }
+        }
+        self
+    }
+
+    /// Returns the rendered output split into lines.
+    /// Styles that are still active from earlier lines are re-applied at the
+    /// beginning of each line and every line ends with a reset, so each line
+    /// can be printed on its own. A trailing newline yields an empty last line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.force_color().fg_red().text("one\ntwo");
+    /// assert_eq!(builder.lines(), vec!["\x1b[31mone\x1b[0m", "\x1b[31mtwo\x1b[0m"]);
+    /// ```
+    pub fn lines(&self) -> Vec<String> {
+        let level = self.output_level();
+        // Lines without any text render as empty strings, even if they carry codes.
+        let render = |line: &[Entity], visible: bool| {
+            if visible {
+                Self::render(line, level)
+            } else {
+                String::new()
+            }
+        };
+        let mut lines = Vec::new();
+        let mut active: Vec<Ansi> = Vec::new();
+        let mut line: Vec<Entity> = Vec::new();
+        let mut visible = false;
+        for entity in &self.content {
+            match entity {
+                Entity::Ansi(ansi) => {
+                    line.push(Entity::Ansi(*ansi));
+                    match ansi {
+                        Ansi::Reset => active.clear(),
+                        _ => active.push(*ansi),
+                    }
+                }
+                Entity::Text(text) => {
+                    let mut pieces = text.split('\n').peekable();
+                    while let Some(piece) = pieces.next() {
+                        if !piece.is_empty() {
+                            line.push(Entity::Text(piece.to_string()));
+                            visible = true;
+                        }
+                        if pieces.peek().is_some() {
+                            lines.push(render(&line, visible));
+                            line = active.iter().map(|ansi| Entity::Ansi(*ansi)).collect();
+                            visible = false;
+                        }
+                    }
+                }
+                Entity::Link { text, .. } | Entity::Raw(text) => {
+                    visible |= !text.is_empty();
+                    line.push(entity.clone());
+                }
+                Entity::Control(_) => line.push(entity.clone()),
+            }
+        }
+        lines.push(render(&line, visible));
+        lines
+    }
+
+    /// Appends the string verbatim, including any escape sequences in it.
+    /// Unlike `text`, the string is always emitted as is, even if colors are disabled,
+    /// which makes it useful to embed output that was already rendered elsewhere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new_no_color();
+    /// builder.bold().text_raw("\x1b[31mred\x1b[0m");
+    /// assert_eq!(builder.as_string(), "\x1b[31mred\x1b[0m");
+    /// ```
+    pub fn text_raw(&mut self, s: &str) -> &mut Self {
+        self.content.push(Entity::Raw(s.to_string()));
+        self
+    }
+
+    /// Appends the text with every line indented by the given number of spaces.
+    /// A trailing newline is kept, but the empty segment after it is not indented.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.indent(2, "a\nb\n");
+    /// assert_eq!(builder.content_raw()[0], Entity::Text("  a\n  b\n".to_string()));
+    /// ```
+    pub fn indent(&mut self, spaces: usize, text: &str) -> &mut Self {
+        if text.is_empty() {
+            return self;
+        }
+        let prefix = " ".repeat(spaces);
+        let indented: String = text
+            .split_inclusive('\n')
+            .map(|line| format!("{}{}", prefix, line))
+            .collect();
+        self.text(&indented)
+    }
+
+    /// Appends the current local time in faint, followed by a reset.
+    /// The format uses the `strftime` syntax of `chrono`, e.g. `%H:%M:%S`.
+    /// If the format is invalid, it is appended as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.timestamp("%H:%M:%S").text(" started");
+    /// assert_eq!(builder.visible_len(), 16);
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn timestamp(&mut self, fmt: &str) -> &mut Self {
+        use std::fmt::Write;
+        let mut time = String::new();
+        if write!(time, "{}", chrono::Local::now().format(fmt)).is_err() {
+            time = fmt.to_string();
+        }
+        self.faint().text(&time).reset()
+    }
+
+    /// Appends a log level colored by its conventional color, followed by a reset.
+    /// `ERROR` is bold red, `WARN` yellow, `INFO` green, `DEBUG` blue and `TRACE` faint.
+    /// The level is matched case-insensitively, unknown levels are appended uncolored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.level("WARN").text(" disk almost full");
+    /// assert_eq!(builder.content_raw()[0], Entity::Ansi(Ansi::FgYellow));
+    /// ```
+    pub fn level(&mut self, level: &str) -> &mut Self {
+        let styles: &[Ansi] = match level.to_ascii_uppercase().as_str() {
+            "ERROR" => &[Ansi::Bold, Ansi::FgRed],
+            "WARN" => &[Ansi::FgYellow],
+            "INFO" => &[Ansi::FgGreen],
+            "DEBUG" => &[Ansi::FgBlue],
+            "TRACE" => &[Ansi::Faint],
+            _ => return self.text(level),
+        };
+        self.scoped(styles, |b| {
+            b.text(level);
+        })
+    }
+
+    /// Returns the text without any ANSI codes, regardless of the color settings.
+    /// Link texts are included and escape sequences are removed from raw text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new_force_color();
+    /// builder.bold().text("Hello, ").fg_red().text("world!").reset();
+    /// assert_eq!(builder.plain_text(), "Hello, world!");
+    /// ```
+    pub fn plain_text(&self) -> String {
+        let mut plain = String::new();
+        for entity in &self.content {
+            match entity {
+                Entity::Text(text) | Entity::Link { text, .. } => plain.push_str(text),
+                Entity::Raw(text) => plain.push_str(&utils::strip_ansi(text)),
+                Entity::Ansi(_) | Entity::Control(_) => {}
+            }
+        }
+        plain
+    }
+
+    /// Returns whether both builders have the same plain text, see `plain_text`.
+    /// ANSI codes and how the text is split into entities are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut red = Builder::new();
+    /// red.fg_red().text("Hello, world!");
+    /// let mut blue = Builder::new();
+    /// blue.fg_blue().text("Hello, ").bold().text("world!");
+    /// assert!(red.text_eq(&blue));
+    /// ```
+    pub fn text_eq(&self, other: &Builder) -> bool {
+        self.plain_text() == other.plain_text()
+    }
+
+    /// Returns the content as markdown.
+    /// Bold becomes `**...**`, italic `*...*` and crossed out `~~...~~`, colors are dropped.
+    /// Open markers are kept on a stack, so a change only closes and opens the markers that changed.
+    /// Links become `[text](url)` with a percent-encoded url and text is escaped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let markdown = Builder::new().bold().text("a").italic().text("b").reset().to_markdown();
+    /// assert_eq!(markdown, "**a*b***");
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        let mut style = StyleState::default();
+        let mut open: Vec<&str> = Vec::new();
+        for entity in &self.content {
+            let text = match entity {
+                Entity::Ansi(ansi) => {
+                    style.apply(*ansi);
+                    continue;
+                }
+                Entity::Control(_) => continue,
+                Entity::Text(text) => escape_markdown(text),
+                Entity::Raw(text) => escape_markdown(&utils::strip_ansi(text)),
+                Entity::Link { text, url } => {
+                    format!("[{}]({})", escape_markdown(text), encode_markdown_url(url))
+                }
+            };
+            if text.is_empty() {
+                continue;
+            }
+            let markers: Vec<&str> = [
+                (style.bold, "**"),
+                (style.italic, "*"),
+                (style.crossed_out, "~~"),
+            ]
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, marker)| *marker)
+            .collect();
+            // Keep the bottom of the stack that is still active and close everything above it.
+            let keep = open
+                .iter()
+                .take_while(|marker| markers.contains(marker))
+                .count();
+            markdown.extend(open.drain(keep..).rev());
+            for marker in markers {
+                if !open.contains(&marker) {
+                    markdown.push_str(marker);
+                    open.push(marker);
+                }
+            }
+            markdown.push_str(&text);
+        }
+        markdown.extend(open.iter().rev().copied());
+        markdown
+    }
+
+    /// Returns the number of ANSI code entities.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().fg_red().text("Hello").reset();
+    /// assert_eq!(builder.count_ansi(), 3);
+    /// ```
+    pub fn count_ansi(&self) -> usize {
+        self.content
+            .iter()
+            .filter(|entity| matches!(entity, Entity::Ansi(_)))
+            .count()
+    }
+
+    /// Returns the number of text entities.
+    /// Links and raw text are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.bold().text("Hello").reset().text(", world!");
+    /// assert_eq!(builder.count_text(), 2);
+    /// ```
+    pub fn count_text(&self) -> usize {
+        self.content
+            .iter()
+            .filter(|entity| matches!(entity, Entity::Text(_)))
+            .count()
+    }
+
+    /// Appends the lines inside a box drawn with Unicode box characters.
+    /// The border is drawn in the given color and sized to the widest visible line,
+    /// with one space of padding on each side. Empty `lines` draw an empty box.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.boxed(&["Hello,", "world!"], Ansi::FgCyan);
+    /// assert_eq!(builder.plain_text(), "┌────────┐\n│ Hello, │\n│ world! │\n└────────┘\n");
+    /// ```
+    pub fn boxed(&mut self, lines: &[&str], color: Ansi) -> &mut Self {
+        let width = lines
+            .iter()
+            .map(|line| utils::visible_len(line))
+            .max()
+            .map_or(0, |width| width + 2);
+        let border = "─".repeat(width);
+        self.ansi(color)
+            .text(&format!("┌{}┐", border))
+            .reset()
+            .newline();
+        for line in lines {
+            let padding = " ".repeat(width - 1 - utils::visible_len(line));
+            self.ansi(color).text("│").reset();
+            self.text(&format!(" {}{}", line, padding));
+            self.ansi(color).text("│").reset().newline();
+        }
+        self.ansi(color)
+            .text(&format!("└{}┘", border))
+            .reset()
+            .newline()
+    }
+
+    /// Runs the closure on a fresh builder and appends its entities.
+    /// This allows reusable styling functions that take a `&mut Builder`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    /// use rcolors::builder::Entity;
+    /// use rcolors::ansi::Ansi;
+    ///
+    /// fn label(b: &mut Builder) {
+    ///     b.bold().text("Label: ").reset();
+    /// }
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.section(label).text("value");
+    /// assert_eq!(builder.content_raw().len(), 4);
+    /// assert_eq!(builder.get(0), Some(&Entity::Ansi(Ansi::Bold)));
+    /// ```
+    pub fn section(&mut self, f: impl FnOnce(&mut Builder)) -> &mut Self {
+        let mut section = Builder::new();
+        f(&mut section);
+        self.content.append(&mut section.content);
+        self
+    }
+
+    /// Reserves capacity for at least `additional` more entities.
+    /// This avoids reallocations when appending many entities in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::builder::Builder;
+    ///
+    /// let mut builder = Builder::new();
+    /// builder.reserve(10).text("Hello");
+    /// assert_eq!(builder.content_raw().len(), 1);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) -> &mut Self {
+        self.content.reserve(additional);
+        self
+    }
+}
+
+// A single step of a diff between two sequences.
+enum Change<'a, T> {
+    Same(&'a T),
+    Added(&'a T),
+    Removed(&'a T),
+}
+
+// Diffs two sequences using their longest common subsequence.
+// On a tie, removals are emitted before additions.
+fn diff_lcs<'a, T: PartialEq>(old: &'a [T], new: &'a [T]) -> Vec<Change<'a, T>> {
+    // Longest common subsequence table, filled from the back.
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            changes.push(Change::Same(&old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            changes.push(Change::Removed(&old[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(&new[j]));
+            j += 1;
+        }
+    }
+    changes
+}
+
+// Writes the collected SGR parameters as one escape sequence.
+fn flush_params(content: &mut String, params: &mut Vec<String>) {
+    if !params.is_empty() {
+        content.push_str(&format!("\x1b[{}m", params.join(";")));
+        params.clear();
+    }
+}
+
+// The style state tracked while exporting HTML and markdown.
+#[derive(Default, PartialEq)]
+struct StyleState {
+    fg: Option<(u8, u8, u8)>,
+    bg: Option<(u8, u8, u8)>,
+    bold: bool,
+    faint: bool,
+    italic: bool,
+    underline: bool,
+    crossed_out: bool,
+    overline: bool,
+}
+
+impl StyleState {
+    fn apply(&mut self, ansi: Ansi) {
+        match ansi {
+            Ansi::Reset => *self = Self::default(),
+            Ansi::Bold => self.bold = true,
+            Ansi::Faint => self.faint = true,
+            Ansi::Italic => self.italic = true,
+            Ansi::Underline | Ansi::DoubleUnderline => self.underline = true,
+            Ansi::CrossedOut => self.crossed_out = true,
+            Ansi::Overline => self.overline = true,
+            Ansi::BoldOff | Ansi::FaintOff => {
+                self.bold = false;
+                self.faint = false;
+            }
+            Ansi::ItalicOff => self.italic = false,
+            Ansi::UnderlineOff => self.underline = false,
+            Ansi::CrossedOutOff => self.crossed_out = false,
+            Ansi::FgDefault => self.fg = None,
+            Ansi::BgDefault => self.bg = None,
+            ansi => match ansi.code() {
+                30..=38 | 90..=97 => self.fg = ansi.rgb(),
+                40..=48 | 100..=107 => self.bg = ansi.rgb(),
+                _ => {}
+            },
+        }
+    }
+
+    fn css(&self) -> String {
+        let mut css = Vec::new();
+        if let Some((r, g, b)) = self.fg {
+            css.push(format!("color:#{:02x}{:02x}{:02x}", r, g, b));
+        }
+        if let Some((r, g, b)) = self.bg {
+            css.push(format!("background-color:#{:02x}{:02x}{:02x}", r, g, b));
+        }
+        if self.bold {
+            css.push("font-weight:bold".to_string());
+        }
+        if self.faint {
+            css.push("opacity:0.5".to_string());
+        }
+        if self.italic {
+            css.push("font-style:italic".to_string());
+        }
+        let decorations: Vec<&str> = [
+            (self.underline, "underline"),
+            (self.crossed_out, "line-through"),
+            (self.overline, "overline"),
+        ]
+        .iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, decoration)| *decoration)
+        .collect();
+        if !decorations.is_empty() {
+            css.push(format!("text-decoration:{}", decorations.join(" ")));
+        }
+        css.join(";")
+    }
+}
+
+// Escapes the characters with a special meaning in markdown.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// Truncates raw text to `max` visible characters, keeping its SGR sequences.
+fn truncate_raw(raw: &str, max: usize) -> String {
+    let mut remaining = max;
+    let mut truncated = String::new();
+    for segment in ansi::tokenize(raw) {
+        match segment {
+            Segment::Sgr(params) => truncated.push_str(&format!("\x1b[{}m", params)),
+            Segment::Text(text) => {
+                let end = text
+                    .char_indices()
+                    .nth(remaining)
+                    .map_or(text.len(), |(i, _)| i);
+                remaining -= text[..end].chars().count();
+                truncated.push_str(&text[..end]);
+            }
+            // Other sequences are not visible, see `utils::visible_len`, and are always kept.
+            Segment::Other(sequence) => truncated.push_str(sequence),
+        }
+    }
+    truncated
+}
+
+// Percent-encodes the characters that would end a markdown link destination.
+fn encode_markdown_url(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len());
+    for c in url.chars() {
+        if c.is_whitespace() || c.is_control() || matches!(c, '(' | ')' | '<' | '>') {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+    encoded
+}
+
+// Escapes the characters with a special meaning in HTML.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Converts a HSV color to RGB, with hue in degrees and saturation/value in 0.0..=1.0.
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let c = v * s;
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let m = v - c;
+    let (r, g, b) = match (h % 360.0) as u32 / 60 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let scale = |v: f64| ((v + m) * 255.0).round() as u8;
+    (scale(r), scale(g), scale(b))
+}
+
+#[cfg(test)]
+mod builder_tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::ansi::Ansi;
+    use crate::utils::testing::EnvGuard;
+
+    #[test]
+    fn builder_new() {
+        let _env = EnvGuard::new(&[]);
+        let builder = Builder::new();
+        assert_eq!(builder.content.len(), 0);
+        assert_eq!(builder.no_color, utils::no_color());
+    }
+
+    #[test]
+    fn builder_text() {
+        let mut builder = Builder::new();
+        builder.text("Hello, world!");
+        match &builder.content[0] {
+            Entity::Text(text) => assert_eq!(text, "Hello, world!"),
+            _ => panic!("Expected text entity"),
+        }
+    }
+
+    #[test]
+    fn builder_ansi() {
+        let mut builder = Builder::new();
+        builder.bold().italic();
+        assert_eq!(builder.content.len(), 2);
+        match &builder.content[0] {
+            Entity::Ansi(Ansi::Bold) => (),
+            _ => panic!("Expected Bold ANSI code"),
+        }
+        match &builder.content[1] {
+            Entity::Ansi(Ansi::Italic) => (),
+            _ => panic!("Expected Italic ANSI code"),
+        }
+    }
+
+    #[test]
+    fn display_with_color() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.text("Hello, ").bold().text("world!").reset();
+        assert_eq!(format!("{}", builder), "Hello, \x1b[1mworld!\x1b[0m");
+    }
+
+    #[test]
+    fn display_without_color() {
+        let mut builder = Builder::new();
+        builder.no_color = true;
+        builder.text("Hello, ").bold().text("world!").reset();
+        assert_eq!(format!("{}", builder), "Hello, world!");
+    }
+
+    #[test]
+    fn builder_reset() {
+        let mut builder = Builder::new();
+        builder.reset();
+        assert_eq!(builder.content.len(), 1);
+        match &builder.content[0] {
+            Entity::Ansi(Ansi::Reset) => (),
+            _ => panic!("Expected Reset ANSI code"),
+        }
+    }
+
+    #[test]
+    fn builder_fg_red() {
+        let mut builder = Builder::new();
+        builder.fg_red();
+        assert_eq!(builder.content.len(), 1);
+        match &builder.content[0] {
+            Entity::Ansi(Ansi::FgRed) => (),
+            _ => panic!("Expected FgRed ANSI code"),
+        }
+    }
+
+    #[test]
+    fn builder_bg_blue() {
+        let mut builder = Builder::new();
+        builder.bg_blue();
+        assert_eq!(builder.content.len(), 1);
+        match &builder.content[0] {
+            Entity::Ansi(Ansi::BgBlue) => (),
+            _ => panic!("Expected BgBlue ANSI code"),
+        }
+    }
+
+    #[test]
+    fn builder_combined_styles() {
+        let mut builder = Builder::new();
+        builder.bold().italic().underline();
+        assert_eq!(builder.content.len(), 3);
+        match &builder.content[0] {
+            Entity::Ansi(Ansi::Bold) => (),
+            _ => panic!("Expected Bold ANSI code"),
+        }
+        match &builder.content[1] {
+            Entity::Ansi(Ansi::Italic) => (),
+            _ => panic!("Expected Italic ANSI code"),
+        }
+        match &builder.content[2] {
+            Entity::Ansi(Ansi::Underline) => (),
+            _ => panic!("Expected Underline ANSI code"),
+        }
+    }
+
+    #[test]
+    fn builder_print() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.text("Hello, ").bold().text("world!").reset();
+        // Redirect stdout for testing
+        let output = std::io::stdout();
+        let mut handle = output.lock();
+        builder.print();
+        assert!(handle.write(b"Hello, \x1b[1mworld!\x1b[0m").is_ok());
+    }
+
+    #[test]
+    fn builder_println() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.text("Hello, ").bold().text("world!").reset();
+        // Redirect stdout for testing
+        let output = std::io::stdout();
+        let mut handle = output.lock();
+        builder.println();
+        assert!(handle.write(b"Hello, \x1b[1mworld!\x1b[0m\n").is_ok());
+    }
+
+    #[test]
+    fn builder_to_string() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.text("Hello, ").bold().text("world!").reset();
+        assert_eq!(builder.as_string(), "Hello, \u{1b}[1mworld!\u{1b}[0m");
+    }
+
+    #[test]
+    fn builder_pad_to_height() {
+        let mut builder = Builder::new();
+        builder.no_color = true;
+        builder.fg_red().text("one\n").reset().text("two");
+        builder.pad_to_height(5);
+        assert_eq!(builder.line_count(), 5);
+        assert_eq!(builder.as_string(), "one\ntwo\n\n\n");
+
+        builder.pad_to_height(3);
+        assert_eq!(builder.line_count(), 5);
+    }
+
+    #[test]
+    fn builder_diff_against() {
+        let mut expected = Builder::new();
+        expected.fg_red().text("error").reset();
+        let mut actual = Builder::new();
+        actual.fg_yellow().text("error").reset();
+        assert_eq!(
+            expected.diff_against(&actual),
+            "\x1b[31m- Ansi(FgRed)\x1b[0m\n\
+             \x1b[32m+ Ansi(FgYellow)\x1b[0m\n  \
+             Text(\"error\")\n  \
+             Ansi(Reset)\n"
+        );
+        assert_eq!(
+            expected.diff_against(&expected),
+            "  Ansi(FgRed)\n  Text(\"error\")\n  Ansi(Reset)\n"
+        );
+    }
+
+    #[test]
+    fn builder_visible_lines() {
+        let mut builder = Builder::new();
+        builder
+            .fg_red()
+            .text("one\ntwo\n")
+            .fg_blue()
+            .text("three\nfour")
+            .reset();
+
+        let visible = builder.visible_lines(1, 2);
+        assert_eq!(
+            visible.content,
+            vec![
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Text("two\n".to_string()),
+                Entity::Ansi(Ansi::FgBlue),
+                Entity::Text("three".to_string()),
+            ]
+        );
+
+        let visible = builder.visible_lines(3, 5);
+        assert_eq!(
+            visible.content,
+            vec![
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Ansi(Ansi::FgBlue),
+                Entity::Text("four".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+
+        builder.force_color();
+        let visible = builder.visible_lines(0, 1);
+        assert!(visible.force_color);
+        assert_eq!(visible.as_string(), "\x1b[31mone\x1b[0m");
+
+        assert!(builder.visible_lines(4, 2).content.is_empty());
+    }
+
+    #[test]
+    fn builder_render_at() {
+        let mut builder = Builder::new();
+        builder.text("Hello, ").bold().text("world!").reset();
+        assert_eq!(builder.render_at(ColorLevel::None), "Hello, world!");
+        assert_eq!(
+            builder.render_at(ColorLevel::Ansi16),
+            "Hello, \x1b[1mworld!\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn builder_render_level() {
+        let mut builder = Builder::new();
+        builder.no_color = true;
+        assert_eq!(builder.render_level(), ColorLevel::None);
+        builder.force_color = true;
+        assert_ne!(builder.render_level(), ColorLevel::None);
+    }
+
+    #[test]
+    fn builder_force_color() {
+        let _env = EnvGuard::new(&[("NO_COLOR", Some("1"))]);
+        let mut builder = Builder::new();
+        builder.bold().text("bold").reset();
+        assert_eq!(builder.as_string(), "bold");
+
+        builder.force_color();
+        assert_eq!(builder.as_string(), "\x1b[1mbold\x1b[0m");
+    }
+
+    #[test]
+    fn empty_builder_renders_empty() {
+        let mut builder = Builder::new();
+        assert_eq!(builder.as_string(), "");
+        builder.force_color = true;
+        assert_eq!(builder.as_string(), "");
+        assert_eq!(builder.to_string(), "");
+    }
+
+    #[test]
+    fn builder_appends_missing_reset() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.text("hi").bold();
+        let rendered = builder.as_string();
+        assert!(rendered.ends_with("\x1b[0m"));
+        assert!(!rendered.starts_with("\x1b[0m"));
+        assert_eq!(rendered, "hi\x1b[1m\x1b[0m");
+    }
+
+    #[test]
+    fn builder_combines_adjacent_codes() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.bold().fg_red().text("x").reset();
+        assert_eq!(builder.as_string(), "\x1b[1;31mx\x1b[0m");
+
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.bold().text("x").fg_red().text("y").reset();
+        assert_eq!(builder.as_string(), "\x1b[1mx\x1b[31my\x1b[0m");
+
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder
+            .bold()
+            .reset()
+            .fg_red()
+            .ansi(Ansi::BgRgb(1, 2, 3))
+            .text("z");
+        assert_eq!(
+            builder.as_string(),
+            "\x1b[1m\x1b[0m\x1b[31;48;2;1;2;3mz\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn builder_visible_len() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder
+            .bold()
+            .fg_red()
+            .text("Hello, ")
+            .reset()
+            .bg_blue()
+            .text("world!")
+            .reset();
+        assert_eq!(builder.visible_len(), 13);
+        assert_eq!(
+            builder.visible_len(),
+            utils::visible_len(&builder.as_string())
+        );
+
+        let mut builder = Builder::new_force_color();
+        builder.cursor_up(2).text("ab");
+        assert_eq!(builder.visible_len(), 2);
+        assert_eq!(utils::visible_len(&builder.as_string()), 2);
+
+        let mut builder = Builder::new_force_color();
+        builder.link("x", "https://example.com");
+        assert_eq!(builder.visible_len(), 1);
+        assert_eq!(utils::visible_len(&builder.as_string()), 1);
+    }
+
+    #[test]
+    fn builder_write_to() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.text("Hello, ").bold().text("world!").reset();
+        let mut buffer: Vec<u8> = Vec::new();
+        builder.write_to(&mut buffer).unwrap();
+        assert_eq!(buffer, builder.as_string().as_bytes());
+        assert_eq!(buffer, b"Hello, \x1b[1mworld!\x1b[0m");
+    }
+
+    #[test]
+    fn builder_new_stderr() {
+        let _env = EnvGuard::new(&[]);
+        let builder = Builder::new_stderr();
+        assert_eq!(builder.stream, Stream::Stderr);
+        assert_eq!(builder.no_color, utils::no_color_for(Stream::Stderr));
+    }
+
+    #[test]
+    fn builder_eprint() {
+        let mut builder = Builder::new_stderr();
+        builder.force_color = true;
+        builder.text("Hello, ").bold().text("world!").reset();
+        builder.eprint();
+        builder.eprintln();
+    }
+
+    #[test]
+    fn builder_gradient() {
+        let mut builder = Builder::new();
+        builder.gradient("abcde", (255, 0, 0), (0, 0, 255));
+        assert_eq!(builder.content.len(), 10);
+        assert_eq!(builder.content[0], Entity::Ansi(Ansi::FgRgb(255, 0, 0)));
+        assert_eq!(builder.content[1], Entity::Text("a".to_string()));
+        assert_eq!(builder.content[4], Entity::Ansi(Ansi::FgRgb(128, 0, 128)));
+        assert_eq!(builder.content[8], Entity::Ansi(Ansi::FgRgb(0, 0, 255)));
+        assert_eq!(builder.content[9], Entity::Text("e".to_string()));
+    }
+
+    #[test]
+    fn builder_gradient_edge_cases() {
+        let mut builder = Builder::new();
+        builder.gradient("", (255, 0, 0), (0, 0, 255));
+        assert!(builder.content.is_empty());
+
+        builder.gradient("x", (255, 0, 0), (0, 0, 255));
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Ansi(Ansi::FgRgb(255, 0, 0)),
+                Entity::Text("x".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_rainbow() {
+        let mut builder = Builder::new();
+        builder.rainbow("abcdef");
+        let codes: Vec<Ansi> = builder
+            .content
+            .iter()
+            .filter_map(|entity| match entity {
+                Entity::Ansi(ansi) => Some(*ansi),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                Ansi::FgRgb(255, 0, 0),
+                Ansi::FgRgb(255, 255, 0),
+                Ansi::FgRgb(0, 255, 0),
+                Ansi::FgRgb(0, 255, 255),
+                Ansi::FgRgb(0, 0, 255),
+                Ansi::FgRgb(255, 0, 255),
+                Ansi::FgDefault,
+            ]
+        );
+
+        let mut builder = Builder::new_force_color();
+        builder.rainbow("ab").text("c");
+        assert!(builder.as_string().ends_with("\x1b[39mc\x1b[0m"));
+    }
+
+    #[test]
+    fn builder_rainbow_whitespace() {
+        let mut builder = Builder::new();
+        builder.rainbow("ab cdef");
+        assert_eq!(builder.content.len(), 14);
+        assert_eq!(builder.content[4], Entity::Text(" ".to_string()));
+
+        let mut builder = Builder::new();
+        builder.rainbow(" ");
+        assert_eq!(builder.content, vec![Entity::Text(" ".to_string())]);
+    }
+
+    #[test]
+    fn builder_clear() {
+        let mut builder = Builder::new();
+        builder.no_color = true;
+        builder.force_color = true;
+        builder.bold().text("Hello, world!").reset();
+        builder.clear();
+        assert!(builder.content_raw().is_empty());
+        assert!(builder.no_color);
+        assert!(builder.force_color);
+
+        builder.text("again");
+        assert_eq!(builder.as_string(), "again");
+    }
+
+    #[test]
+    fn builder_len() {
+        let mut builder = Builder::new();
+        assert_eq!(builder.len(), 0);
+        assert!(builder.is_empty());
+        assert_eq!(builder.text_len(), 0);
+
+        builder.text("Hello, ").text("world!");
+        assert_eq!(builder.len(), 2);
+        assert!(!builder.is_empty());
+        assert_eq!(builder.text_len(), 13);
+
+        builder.bold().text("äö").reset();
+        assert_eq!(builder.len(), 5);
+        assert_eq!(builder.text_len(), 17);
+    }
+
+    #[test]
+    fn builder_add() {
+        let mut left = Builder::new();
+        left.force_color = true;
+        left.fg_red().text("Hello, ");
+        let mut right = Builder::new();
+        right.no_color = true;
+        right.bold().text("world!").reset();
+
+        let joined = left + right;
+        assert!(joined.force_color);
+        assert_eq!(
+            joined.content,
+            vec![
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Text("Hello, ".to_string()),
+                Entity::Ansi(Ansi::Bold),
+                Entity::Text("world!".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_extend_from() {
+        let mut other = Builder::new();
+        other.bold().text("world!");
+        let mut builder = Builder::new();
+        builder.text("Hello, ").extend_from(&other).reset();
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Text("Hello, ".to_string()),
+                Entity::Ansi(Ansi::Bold),
+                Entity::Text("world!".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+        assert_eq!(other.content.len(), 2);
+    }
+
+    #[test]
+    fn builder_from_iter() {
+        let _env = EnvGuard::new(&[]);
+        let entities = vec![
+            Entity::Ansi(Ansi::FgRed),
+            Entity::Text("Hello, ".to_string()),
+        ];
+        let mut builder = Builder::from_iter(entities.clone());
+        assert_eq!(builder.content, entities);
+        assert_eq!(builder.no_color, utils::no_color());
+        assert!(!builder.force_color);
+
+        builder.extend(vec![Entity::Text("world!".to_string())]);
+        assert_eq!(builder.content.len(), 3);
+
+        let collected: Builder = builder.content.iter().cloned().collect();
+        assert_eq!(collected.content, builder.content);
+    }
+
+    #[test]
+    fn builder_line_styles() {
+        let mut builder = Builder::new();
+        builder.double_underline().overline();
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Ansi(Ansi::DoubleUnderline),
+                Entity::Ansi(Ansi::Overline),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_off_styles() {
+        let mut builder = Builder::new();
+        builder
+            .not_bold()
+            .not_faint()
+            .not_italic()
+            .not_underline()
+            .not_blink()
+            .not_reverse()
+            .not_concealed()
+            .not_crossed_out()
+            .fg_default()
+            .bg_default();
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Ansi(Ansi::BoldOff),
+                Entity::Ansi(Ansi::FaintOff),
+                Entity::Ansi(Ansi::ItalicOff),
+                Entity::Ansi(Ansi::UnderlineOff),
+                Entity::Ansi(Ansi::BlinkOff),
+                Entity::Ansi(Ansi::ReverseOff),
+                Entity::Ansi(Ansi::ConcealedOff),
+                Entity::Ansi(Ansi::CrossedOutOff),
+                Entity::Ansi(Ansi::FgDefault),
+                Entity::Ansi(Ansi::BgDefault),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_presets() {
+        let mut error = Builder::error("x");
+        error.force_color = true;
+        assert_eq!(error.as_string(), "\x1b[1;31mx\x1b[0m");
+
+        let mut warn = Builder::warn("x");
+        warn.force_color = true;
+        assert_eq!(warn.as_string(), "\x1b[33mx\x1b[0m");
+
+        let mut info = Builder::info("x");
+        info.force_color = true;
+        assert_eq!(info.as_string(), "\x1b[34mx\x1b[0m");
+
+        let mut success = Builder::success("x");
+        success.force_color = true;
+        assert_eq!(success.as_string(), "\x1b[32mx\x1b[0m");
+    }
+
+    #[test]
+    fn builder_colored() {
+        let mut colored = Builder::colored("x", Ansi::FgRed);
+        colored.force_color = true;
+        assert_eq!(colored.as_string(), "\x1b[31mx\x1b[0m");
+    }
+
+    #[test]
+    fn builder_get() {
+        let mut builder = Builder::new();
+        assert_eq!(builder.get(0), None);
+        assert_eq!(builder.last(), None);
+
+        builder.fg_red().text("Hello, world!");
+        assert_eq!(builder.get(0), Some(&Entity::Ansi(Ansi::FgRed)));
+        assert_eq!(
+            builder.get(1),
+            Some(&Entity::Text("Hello, world!".to_string()))
+        );
+        assert_eq!(builder.get(2), None);
+        assert_eq!(
+            builder.last(),
+            Some(&Entity::Text("Hello, world!".to_string()))
+        );
+    }
+
+    #[test]
+    fn builder_pop() {
+        let mut builder = Builder::new();
+        assert_eq!(builder.pop(), None);
+
+        builder.bold().text("Hello, world!");
+        assert_eq!(
+            builder.pop(),
+            Some(Entity::Text("Hello, world!".to_string()))
+        );
+        assert_eq!(builder.content, vec![Entity::Ansi(Ansi::Bold)]);
+    }
+
+    #[test]
+    fn builder_text_fmt() {
+        let mut builder = Builder::new();
+        let name = "world";
+        builder
+            .text_fmt(format_args!("x={}", 1))
+            .bold()
+            .text_fmt(format_args!("Hello, {name}!"));
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Text("x=1".to_string()),
+                Entity::Ansi(Ansi::Bold),
+                Entity::Text("Hello, world!".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_when() {
+        let mut builder = Builder::new();
+        builder
+            .when(true, |b| {
+                b.bold();
+            })
+            .when(false, |b| {
+                b.faint();
+            })
+            .text("text");
+        assert_eq!(
+            builder.content,
+            vec![Entity::Ansi(Ansi::Bold), Entity::Text("text".to_string())]
+        );
+    }
+
+    #[test]
+    fn builder_link() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.fg_blue().link("docs", "https://docs.rs").reset();
+        assert_eq!(
+            builder.content[1],
+            Entity::Link {
+                text: "docs".to_string(),
+                url: "https://docs.rs".to_string()
+            }
+        );
+        assert_eq!(
+            builder.as_string(),
+            "\x1b[34m\x1b]8;;https://docs.rs\x1b\\docs\x1b]8;;\x1b\\\x1b[0m"
+        );
+        assert_eq!(builder.visible_len(), 4);
+    }
+
+    #[test]
+    fn builder_link_without_color() {
+        let mut builder = Builder::new();
+        builder.no_color = true;
+        builder.fg_blue().link("docs", "https://docs.rs").reset();
+        assert_eq!(builder.as_string(), "docs");
+    }
+
+    #[test]
+    fn builder_render_at_downgrades_rgb() {
+        let mut builder = Builder::new();
+        builder.ansi(Ansi::FgRgb(255, 0, 0)).text("red").reset();
+        assert_eq!(builder.render_at(ColorLevel::Ansi16), "\x1b[31mred\x1b[0m");
+        assert_eq!(
+            builder.render_at(ColorLevel::TrueColor),
+            "\x1b[38;2;255;0;0mred\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn builder_auto_downsample() {
+        let _env = EnvGuard::new(&[
+            ("FORCE_COLOR", Some("1")),
+            ("COLORTERM", None),
+            ("TERM", Some("xterm")),
+        ]);
+        assert_eq!(utils::color_level(), ColorLevel::Ansi16);
+        let mut builder = Builder::new();
+        builder
+            .ansi(Ansi::FgRgb(255, 0, 0))
+            .ansi(Ansi::Bg256(21))
+            .text("x")
+            .reset();
+        assert_eq!(builder.as_string(), "\x1b[38;2;255;0;0;48;5;21mx\x1b[0m");
+
+        builder.auto_downsample(true);
+        assert_eq!(builder.as_string(), "\x1b[31;44mx\x1b[0m");
+
+        std::env::set_var("TERM", "xterm-256color");
+        assert_eq!(builder.as_string(), "\x1b[38;5;196;48;5;21mx\x1b[0m");
+    }
+
+    #[test]
+    fn builder_print_downsamples() {
+        let _env = EnvGuard::new(&[
+            ("FORCE_COLOR", Some("1")),
+            ("COLORTERM", None),
+            ("TERM", Some("xterm")),
+        ]);
         let mut builder = Builder::new();
-        builder.fg_red();
-        assert_eq!(builder.content.len(), 1);
-        match &builder.content[0] {
-            Entity::Ansi(Ansi::FgRed) => (),
-            _ => panic!("Expected FgRed ANSI code"),
-        }
+        builder.ansi(Ansi::FgRgb(255, 0, 0)).text("x").reset();
+        let mut buffer: Vec<u8> = Vec::new();
+        builder.print_into(&mut buffer).unwrap();
+        assert_eq!(buffer, b"\x1b[31mx\x1b[0m");
+        assert_eq!(builder.as_string(), "\x1b[38;2;255;0;0mx\x1b[0m");
     }
 
     #[test]
-    fn builder_bg_blue() {
+    fn builder_push() {
         let mut builder = Builder::new();
-        builder.bg_blue();
-        assert_eq!(builder.content.len(), 1);
-        match &builder.content[0] {
-            Entity::Ansi(Ansi::BgBlue) => (),
-            _ => panic!("Expected BgBlue ANSI code"),
+        builder
+            .push(Entity::Text("Hello, ".to_string()))
+            .push(Entity::Ansi(Ansi::Bold));
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Text("Hello, ".to_string()),
+                Entity::Ansi(Ansi::Bold)
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_iter() {
+        let mut builder = Builder::new();
+        builder
+            .bold()
+            .text("Hello, ")
+            .fg_red()
+            .text("world!")
+            .reset();
+        assert_eq!(builder.iter().count(), 5);
+        let texts = builder
+            .iter()
+            .filter(|entity| matches!(entity, Entity::Text(_)))
+            .count();
+        assert_eq!(texts, 2);
+
+        let mut count = 0;
+        for _ in &builder {
+            count += 1;
         }
+        assert_eq!(count, 5);
     }
 
     #[test]
-    fn builder_combined_styles() {
+    fn builder_map_text() {
         let mut builder = Builder::new();
-        builder.bold().italic().underline();
-        assert_eq!(builder.content.len(), 3);
-        match &builder.content[0] {
-            Entity::Ansi(Ansi::Bold) => (),
-            _ => panic!("Expected Bold ANSI code"),
+        builder
+            .bold()
+            .text("hello, ")
+            .fg_red()
+            .text("world!")
+            .reset()
+            .map_text(|text| text.to_uppercase());
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Ansi(Ansi::Bold),
+                Entity::Text("HELLO, ".to_string()),
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Text("WORLD!".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_repeat_text() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.repeat_text("-", 0);
+        assert!(builder.content.is_empty());
+
+        builder.faint().repeat_text("-", 5).reset();
+        assert_eq!(builder.as_string(), "\x1b[2m-----\x1b[0m");
+    }
+
+    #[test]
+    fn builder_style() {
+        let style = Style {
+            fg: Some(Ansi::FgRed),
+            bg: None,
+            attrs: vec![Ansi::Bold],
+        };
+        let mut builder = Builder::new();
+        builder.style(&style, "error");
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Ansi(Ansi::Bold),
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Text("error".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_with_capacity() {
+        let _env = EnvGuard::new(&[]);
+        let mut builder = Builder::with_capacity(8);
+        assert!(builder.content.is_empty());
+        assert!(builder.content.capacity() >= 8);
+        assert_eq!(builder.no_color, utils::no_color());
+
+        builder.bold().text("text");
+        assert_eq!(builder.len(), 2);
+    }
+
+    #[test]
+    fn builder_truncate_visible() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder
+            .fg_red()
+            .text("Hello, ")
+            .bold()
+            .text("wörld")
+            .fg_blue()
+            .text("!")
+            .truncate_visible(9);
+        assert_eq!(builder.visible_len(), 9);
+        assert_eq!(
+            builder.as_string(),
+            "\x1b[31mHello, \x1b[1mwö\x1b[34m\x1b[0m"
+        );
+
+        let mut builder = Builder::new();
+        builder.text("short").truncate_visible(10);
+        assert_eq!(builder.content, vec![Entity::Text("short".to_string())]);
+
+        let mut builder = Builder::new();
+        builder
+            .text("ab")
+            .text_raw("\x1b[1mcd\x1b[0mef")
+            .text_raw("gh")
+            .truncate_visible(5);
+        assert_eq!(builder.visible_len(), 5);
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Text("ab".to_string()),
+                Entity::Raw("\x1b[1mcd\x1b[0me".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+
+        let mut builder = Builder::new();
+        builder
+            .text_raw("\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\")
+            .truncate_visible(2);
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Raw("\x1b]8;;https://example.com\x1b\\li\x1b]8;;\x1b\\".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_center() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.fg_red().text("abc").reset().center(9);
+        assert_eq!(builder.as_string(), "   \x1b[31mabc\x1b[0m   ");
+
+        let mut builder = Builder::new();
+        builder.text("ab").center(5);
+        assert_eq!(builder.render_at(ColorLevel::None), " ab  ");
+
+        let mut builder = Builder::new();
+        builder.text("too wide").center(4).pad_left(4).pad_right(4);
+        assert_eq!(builder.content, vec![Entity::Text("too wide".to_string())]);
+    }
+
+    #[test]
+    fn builder_pad() {
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.bold().text("42").reset().pad_left(5);
+        assert_eq!(builder.as_string(), "   \x1b[1m42\x1b[0m");
+
+        let mut builder = Builder::new();
+        builder.force_color = true;
+        builder.bold().text("42").reset().pad_right(5);
+        assert_eq!(builder.as_string(), "\x1b[1m42\x1b[0m   ");
+    }
+
+    #[test]
+    fn builder_from_ansi_string() {
+        let builder = Builder::from_ansi_string(&color_sprint!(Ansi::FgRed, "hi"));
+        assert_eq!(
+            builder.content_raw(),
+            vec![
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Text("hi".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+
+        let builder = Builder::from_ansi_string("a\x1b[38;5;208;99mb\x1b[2Jc\x1b[m");
+        assert_eq!(
+            builder.content_raw(),
+            vec![
+                Entity::Text("a".to_string()),
+                Entity::Ansi(Ansi::Fg256(208)),
+                Entity::Text("b\x1b[2Jc".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+
+        let builder = Builder::from_ansi_string("\x1b[1mx\x1b[31");
+        assert_eq!(
+            builder.content_raw(),
+            vec![
+                Entity::Ansi(Ansi::Bold),
+                Entity::Text("x\x1b[31".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_coalesce_text() {
+        let mut builder = Builder::new();
+        builder
+            .fg_red()
+            .text("a")
+            .text("b")
+            .text("c")
+            .coalesce_text();
+        assert_eq!(
+            builder.content_raw(),
+            vec![Entity::Ansi(Ansi::FgRed), Entity::Text("abc".to_string()),]
+        );
+    }
+
+    #[test]
+    fn builder_to_html() {
+        let html = Builder::new().fg_red().text("x").reset().to_html();
+        assert_eq!(html, "<span style=\"color:#aa0000\">x</span>");
+
+        let html = Builder::new()
+            .bold()
+            .ansi(Ansi::BgRgb(1, 2, 3))
+            .text("<a>")
+            .not_bold()
+            .text("&")
+            .reset()
+            .text("plain")
+            .to_html();
+        assert_eq!(
+            html,
+            "<span style=\"background-color:#010203;font-weight:bold\">&lt;a&gt;</span>\
+             <span style=\"background-color:#010203\">&amp;</span>plain"
+        );
+    }
+
+    #[test]
+    fn builder_set_color_enabled() {
+        let mut builder = Builder::new();
+        builder.fg_red().text("red").reset();
+
+        builder.set_color_enabled(false);
+        assert!(builder.no_color);
+        assert!(!builder.force_color);
+        assert_eq!(builder.as_string(), "red");
+
+        builder.set_color_enabled(true);
+        assert!(!builder.no_color);
+        assert!(builder.force_color);
+        assert_eq!(builder.as_string(), "\x1b[31mred\x1b[0m");
+
+        builder.force_color().set_color_enabled(false);
+        assert_eq!(builder.as_string(), "red");
+    }
+
+    #[test]
+    fn builder_new_no_color() {
+        let _env = EnvGuard::new(&[("NO_COLOR", None), ("FORCE_COLOR", Some("1"))]);
+        let mut builder = Builder::new_no_color();
+        builder.fg_red().text("red").reset();
+        assert_eq!(builder.as_string(), "red");
+    }
+
+    #[test]
+    fn builder_new_force_color() {
+        let _env = EnvGuard::new(&[("NO_COLOR", Some("1")), ("FORCE_COLOR", None)]);
+        let mut builder = Builder::new_force_color();
+        builder.fg_red().text("red").reset();
+        assert_eq!(builder.as_string(), "\x1b[31mred\x1b[0m");
+    }
+
+    #[test]
+    fn builder_io_write() {
+        let mut builder = Builder::new();
+        write!(builder, "x={}", 1).unwrap();
+        writeln!(builder, "!").unwrap();
+        builder.flush().unwrap();
+        builder.coalesce_text();
+        assert_eq!(
+            builder.content_raw(),
+            vec![Entity::Text("x=1!\n".to_string())]
+        );
+    }
+
+    #[test]
+    fn builder_io_write_partial_utf8() {
+        let mut builder = Builder::new();
+        let bytes = "ä".as_bytes();
+        assert_eq!(builder.write(&bytes[..1]).unwrap(), 1);
+        assert!(builder.is_empty());
+        assert_eq!(builder.write(&bytes[1..]).unwrap(), 1);
+        assert_eq!(builder.content_raw(), vec![Entity::Text("ä".to_string())]);
+        assert_eq!(
+            builder.write(&[0xff]).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn builder_to_bytes() {
+        let mut builder = Builder::new();
+        builder.fg_red().text("red ä").reset();
+        assert_eq!(builder.to_bytes(), builder.as_string().into_bytes());
+        builder.set_color_enabled(false);
+        assert_eq!(builder.to_bytes(), "red ä".as_bytes());
+    }
+
+    #[test]
+    fn builder_underline_color() {
+        let mut builder = Builder::new();
+        builder.force_color();
+        builder
+            .underline()
+            .underline_color_rgb(255, 0, 0)
+            .text("a")
+            .underline_color_256(208)
+            .text("b")
+            .underline_color_default()
+            .text("c");
+        assert_eq!(
+            builder.as_string(),
+            "\x1b[4;58;2;255;0;0ma\x1b[58;5;208mb\x1b[59mc\x1b[0m"
+        );
+        assert_eq!(builder.render_at(ColorLevel::Ansi16), "\x1b[4mabc\x1b[0m");
+    }
+
+    #[test]
+    fn builder_dim() {
+        let mut builder = Builder::new();
+        builder.dim();
+        assert_eq!(builder.content_raw(), vec![Entity::Ansi(Ansi::Faint)]);
+    }
+
+    #[test]
+    fn builder_multicolor_words() {
+        let palette = [Ansi::FgRed, Ansi::FgGreen, Ansi::FgBlue];
+        let mut builder = Builder::new();
+        builder.multicolor_words("one two  three four", &palette);
+        let word = |color: Ansi, text: &str| {
+            vec![
+                Entity::Ansi(color),
+                Entity::Text(text.to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        };
+        let space = |text: &str| vec![Entity::Text(text.to_string())];
+        let expected: Vec<Entity> = [
+            word(Ansi::FgRed, "one"),
+            space(" "),
+            word(Ansi::FgGreen, "two"),
+            space("  "),
+            word(Ansi::FgBlue, "three"),
+            space(" "),
+            word(Ansi::FgRed, "four"),
+        ]
+        .concat();
+        assert_eq!(builder.content_raw(), expected);
+
+        let mut builder = Builder::new();
+        builder.multicolor_words("one two", &[]);
+        assert_eq!(
+            builder.content_raw(),
+            vec![Entity::Text("one two".to_string())]
+        );
+    }
+
+    #[test]
+    fn builder_cursor_movement() {
+        let mut builder = Builder::new();
+        builder
+            .cursor_up(1)
+            .cursor_down(2)
+            .cursor_forward(3)
+            .cursor_back(4)
+            .cursor_to(5, 6)
+            .bold()
+            .text("x");
+        builder.set_color_enabled(true);
+        assert_eq!(
+            builder.as_string(),
+            "\x1b[1A\x1b[2B\x1b[3C\x1b[4D\x1b[5;6H\x1b[1mx\x1b[0m"
+        );
+        builder.set_color_enabled(false);
+        assert_eq!(builder.as_string(), "x");
+        assert_eq!(builder.visible_len(), 1);
+    }
+
+    #[test]
+    fn builder_erase_codes() {
+        let mut builder = Builder::new_force_color();
+        builder.clear_screen().clear_line().clear_to_eol().reset();
+        assert_eq!(builder.to_bytes(), b"\x1b[2J\x1b[2K\x1b[0K\x1b[0m");
+        builder.set_color_enabled(false);
+        assert_eq!(builder.as_string(), "");
+    }
+
+    #[test]
+    fn builder_set_title() {
+        let mut builder = Builder::new_force_color();
+        builder.set_title("my\x07 app").text("x").reset();
+        assert_eq!(builder.as_string(), "\x1b]0;my app\x07x\x1b[0m");
+        builder.set_color_enabled(false);
+        assert_eq!(builder.as_string(), "x");
+    }
+
+    #[test]
+    fn builder_scoped() {
+        let mut builder = Builder::new();
+        builder.scoped(&[Ansi::Bold, Ansi::FgRed], |b| {
+            b.text("a").fg_blue().text("b");
+        });
+        let content = builder.content_raw();
+        assert_eq!(
+            content[..2],
+            [Entity::Ansi(Ansi::Bold), Entity::Ansi(Ansi::FgRed)]
+        );
+        assert_eq!(content.last(), Some(&Entity::Ansi(Ansi::Reset)));
+
+        let mut builder = Builder::new();
+        builder.scoped(&[Ansi::Italic], |_| {});
+        assert_eq!(
+            builder.content_raw(),
+            vec![Entity::Ansi(Ansi::Italic), Entity::Ansi(Ansi::Reset)]
+        );
+    }
+
+    #[test]
+    fn builder_whitespace_methods() {
+        let mut builder = Builder::new();
+        builder.newline().tab().space().newline_n(2).newline_n(0);
+        assert_eq!(
+            builder.content_raw(),
+            vec![
+                Entity::Text("\n".to_string()),
+                Entity::Text("\t".to_string()),
+                Entity::Text(" ".to_string()),
+                Entity::Text("\n\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_rgb_methods() {
+        let mut builder = Builder::new_force_color();
+        builder.fg_rgb(255, 128, 0).bg_rgb(0, 0, 128).text("x");
+        assert_eq!(builder.content[0], Entity::Ansi(Ansi::FgRgb(255, 128, 0)));
+        assert_eq!(builder.content[1], Entity::Ansi(Ansi::BgRgb(0, 0, 128)));
+        assert_eq!(
+            builder.as_string(),
+            "\x1b[38;2;255;128;0;48;2;0;0;128mx\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn builder_256_methods() {
+        let mut builder = Builder::new_force_color();
+        builder.fg_256(208).text("a").reset().bg_256(17).text("b");
+        assert_eq!(builder.content[0], Entity::Ansi(Ansi::Fg256(208)));
+        assert_eq!(builder.content[3], Entity::Ansi(Ansi::Bg256(17)));
+        assert_eq!(
+            builder.as_string(),
+            "\x1b[38;5;208ma\x1b[0m\x1b[48;5;17mb\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn builder_hex_methods() {
+        let mut builder = Builder::new();
+        builder.fg_hex("#f80").unwrap().bg_hex("000080").unwrap();
+        assert_eq!(
+            builder.content_raw(),
+            vec![
+                Entity::Ansi(Ansi::FgRgb(255, 136, 0)),
+                Entity::Ansi(Ansi::BgRgb(0, 0, 128)),
+            ]
+        );
+        assert_eq!(
+            builder.fg_hex("#12345").unwrap_err(),
+            ParseColorError::InvalidLength(5)
+        );
+        assert_eq!(
+            builder.bg_hex("#gg0000").unwrap_err(),
+            ParseColorError::InvalidDigit('g')
+        );
+        assert_eq!(builder.len(), 2);
+
+        builder
+            .fg_hex_or("#zzz", Ansi::FgRed)
+            .bg_hex_or("#fff", Ansi::BgRed);
+        assert_eq!(builder.get(2), Some(&Entity::Ansi(Ansi::FgRed)));
+        assert_eq!(
+            builder.get(3),
+            Some(&Entity::Ansi(Ansi::BgRgb(255, 255, 255)))
+        );
+    }
+
+    #[test]
+    fn builder_insert() {
+        let mut builder = Builder::new();
+        builder.text("a").text("b");
+        builder
+            .insert(1, Entity::Ansi(Ansi::FgRed))
+            .insert(3, Entity::Ansi(Ansi::Reset));
+        assert_eq!(
+            builder.content_raw(),
+            vec![
+                Entity::Text("a".to_string()),
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Text("b".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_insert_out_of_bounds() {
+        Builder::new().insert(1, Entity::Ansi(Ansi::Bold));
+    }
+
+    #[test]
+    fn builder_replace() {
+        let mut builder = Builder::new();
+        builder
+            .text("Hi {name}, ")
+            .bold()
+            .text("{name}")
+            .reset()
+            .text("{na")
+            .text("me}")
+            .replace("{name}", "Rust");
+        assert_eq!(
+            builder.content_raw(),
+            vec![
+                Entity::Text("Hi Rust, ".to_string()),
+                Entity::Ansi(Ansi::Bold),
+                Entity::Text("Rust".to_string()),
+                Entity::Ansi(Ansi::Reset),
+                Entity::Text("{na".to_string()),
+                Entity::Text("me}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_diff() {
+        let diff = Builder::diff("one\ntwo\nthree", "one\nzwei\nthree\nfour");
+        let text = |s: &str| Entity::Text(s.to_string());
+        assert_eq!(
+            diff.content_raw(),
+            vec![
+                text("  one"),
+                text("\n"),
+                Entity::Ansi(Ansi::FgRed),
+                text("- two"),
+                Entity::Ansi(Ansi::Reset),
+                text("\n"),
+                Entity::Ansi(Ansi::FgGreen),
+                text("+ zwei"),
+                Entity::Ansi(Ansi::Reset),
+                text("\n"),
+                text("  three"),
+                text("\n"),
+                Entity::Ansi(Ansi::FgGreen),
+                text("+ four"),
+                Entity::Ansi(Ansi::Reset),
+                text("\n"),
+            ]
+        );
+        let mut diff = diff;
+        diff.force_color();
+        assert!(diff.as_string().ends_with("\x1b[32m+ four\x1b[0m\n"));
+    }
+
+    #[test]
+    fn builder_expand_tabs() {
+        let mut builder = Builder::new();
+        builder
+            .fg_red()
+            .text("a")
+            .reset()
+            .text("\tb\n")
+            .bold()
+            .text("abc")
+            .reset()
+            .text("\tc\n\t\td")
+            .expand_tabs(4);
+        let plain = utils::strip_ansi(&builder.render_at(ColorLevel::TrueColor));
+        assert_eq!(plain, "a   b\nabc c\n        d");
+
+        builder.text("\tx").expand_tabs(0);
+        assert!(builder.render_at(ColorLevel::None).ends_with("dx"));
+    }
+
+    #[test]
+    fn builder_lines() {
+        let mut builder = Builder::new_force_color();
+        builder
+            .bold()
+            .fg_green()
+            .text("first\nsec")
+            .reset()
+            .text("ond\n");
+        assert_eq!(
+            builder.lines(),
+            vec![
+                "\x1b[1;32mfirst\x1b[0m".to_string(),
+                "\x1b[1;32msec\x1b[0mond".to_string(),
+                String::new(),
+            ]
+        );
+
+        builder.set_color_enabled(false);
+        assert_eq!(builder.lines(), vec!["first", "second", ""]);
+
+        let mut builder = Builder::new_force_color();
+        builder
+            .fg_red()
+            .text("a\nb")
+            .fg_blue()
+            .text("c\n\nd")
+            .reset();
+        assert_eq!(
+            builder.lines(),
+            vec![
+                "\x1b[31ma\x1b[0m".to_string(),
+                "\x1b[31mb\x1b[34mc\x1b[0m".to_string(),
+                String::new(),
+                "\x1b[31;34md\x1b[0m".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn builder_render_cache() {
+        let mut builder = Builder::new_force_color();
+        builder.fg_red().text("a");
+        assert_eq!(builder.as_string(), "\x1b[31ma\x1b[0m");
+        assert!(builder.content.cache.lock().unwrap().is_some());
+        assert_eq!(builder.as_string(), "\x1b[31ma\x1b[0m");
+
+        builder.text("b");
+        assert!(builder.content.cache.lock().unwrap().is_none());
+        assert_eq!(builder.as_string(), "\x1b[31mab\x1b[0m");
+
+        builder.map_text(|text| text.to_uppercase());
+        assert_eq!(builder.as_string(), "\x1b[31mAB\x1b[0m");
+        assert_eq!(builder.render_at(ColorLevel::None), "AB");
+
+        let mut clone = builder.clone();
+        clone.pop();
+        assert_eq!(clone.as_string(), "\x1b[31mA\x1b[0m");
+        assert_eq!(builder.as_string(), "\x1b[31mAB\x1b[0m");
+    }
+
+    #[test]
+    fn builder_text_raw() {
+        let external = color_sprint!(Ansi::FgRed, "red");
+        let mut builder = Builder::new_force_color();
+        builder
+            .bold()
+            .text("a ")
+            .text_raw(&external)
+            .text(" b")
+            .reset();
+        assert_eq!(builder.as_string(), "\x1b[1ma \x1b[31mred\x1b[0m b\x1b[0m");
+        assert_eq!(builder.visible_len(), 7);
+
+        builder.set_color_enabled(false);
+        assert_eq!(builder.as_string(), "a \x1b[31mred\x1b[0m b");
+    }
+
+    #[test]
+    fn builder_indent() {
+        let mut builder = Builder::new();
+        builder.indent(2, "one\ntwo\nthree");
+        assert_eq!(
+            builder.content_raw(),
+            vec![Entity::Text("  one\n  two\n  three".to_string())]
+        );
+
+        let mut builder = Builder::new();
+        builder.indent(2, "one\n\nthree\n").indent(4, "");
+        assert_eq!(
+            builder.content_raw(),
+            vec![Entity::Text("  one\n  \n  three\n".to_string())]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn builder_timestamp() {
+        let mut builder = Builder::new_force_color();
+        builder.timestamp("%Y-%m-%d").text(" x");
+        let rendered = builder.as_string();
+        assert!(rendered.starts_with("\x1b[2m"));
+        assert_eq!(builder.content[2], Entity::Ansi(Ansi::Reset));
+        let Entity::Text(date) = &builder.content[1] else {
+            panic!("expected the formatted time");
+        };
+        assert_eq!(date.len(), 10);
+        assert!(date
+            .chars()
+            .filter(|c| *c != '-')
+            .all(|c| c.is_ascii_digit()));
+
+        let mut builder = Builder::new();
+        builder.timestamp("%Q");
+        assert_eq!(builder.content[1], Entity::Text("%Q".to_string()));
+    }
+
+    #[test]
+    fn builder_level() {
+        let cases = [
+            ("ERROR", "\x1b[1;31mERROR\x1b[0m"),
+            ("WARN", "\x1b[33mWARN\x1b[0m"),
+            ("INFO", "\x1b[32mINFO\x1b[0m"),
+            ("debug", "\x1b[34mdebug\x1b[0m"),
+            ("TRACE", "\x1b[2mTRACE\x1b[0m"),
+        ];
+        for (level, expected) in cases {
+            let mut builder = Builder::new_force_color();
+            builder.level(level);
+            assert_eq!(builder.as_string(), expected);
         }
-        match &builder.content[1] {
-            Entity::Ansi(Ansi::Italic) => (),
-            _ => panic!("Expected Italic ANSI code"),
+
+        let mut builder = Builder::new();
+        builder.level("FATAL");
+        assert_eq!(
+            builder.content_raw(),
+            vec![Entity::Text("FATAL".to_string())]
+        );
+    }
+
+    #[test]
+    fn builder_plain_text() {
+        let mut builder = Builder::new_force_color();
+        builder
+            .bold()
+            .text("one ")
+            .italic()
+            .fg_rgb(1, 2, 3)
+            .text("two ")
+            .reset()
+            .underline()
+            .link("three", "https://example.com")
+            .clear_line()
+            .text_raw("\x1b[31m four\x1b[0m");
+        assert_eq!(builder.plain_text(), "one two three four");
+    }
+
+    #[test]
+    fn builder_text_eq() {
+        let mut left = Builder::new();
+        left.fg_red().text("status: ").bold().text("ok").reset();
+        let mut right = Builder::new();
+        right.text("status").bg_green().text(": ok");
+        assert!(left.text_eq(&right));
+        assert!(right.text_eq(&left));
+
+        right.text("!");
+        assert!(!left.text_eq(&right));
+    }
+
+    #[test]
+    fn builder_to_markdown() {
+        let cases = [
+            (Ansi::Bold, "**x**"),
+            (Ansi::Italic, "*x*"),
+            (Ansi::CrossedOut, "~~x~~"),
+            (Ansi::FgRed, "x"),
+        ];
+        for (ansi, expected) in cases {
+            let markdown = Builder::new().ansi(ansi).text("x").reset().to_markdown();
+            assert_eq!(markdown, expected);
         }
-        match &builder.content[2] {
-            Entity::Ansi(Ansi::Underline) => (),
-            _ => panic!("Expected Underline ANSI code"),
+
+        let markdown = Builder::new()
+            .crossed_out()
+            .text("a")
+            .bold()
+            .text("b")
+            .not_crossed_out()
+            .text("c")
+            .reset()
+            .text("2*3")
+            .to_markdown();
+        assert_eq!(markdown, "~~a**b**~~**c**2\\*3");
+
+        let markdown = Builder::new()
+            .bold()
+            .text("a")
+            .italic()
+            .text("b")
+            .not_italic()
+            .text("c")
+            .reset()
+            .to_markdown();
+        assert_eq!(markdown, "**a*b*c**");
+
+        let markdown = Builder::new()
+            .link("docs", "https://example.com/a (b)/<c>")
+            .to_markdown();
+        assert_eq!(markdown, "[docs](https://example.com/a%20%28b%29/%3Cc%3E)");
+    }
+
+    #[test]
+    fn builder_count_entities() {
+        let mut builder = Builder::new();
+        assert_eq!(builder.count_ansi(), 0);
+        assert_eq!(builder.count_text(), 0);
+
+        builder
+            .bold()
+            .text("a")
+            .fg_256(1)
+            .text("b")
+            .link("c", "https://example.com")
+            .cursor_up(1)
+            .text("d")
+            .reset();
+        assert_eq!(builder.count_ansi(), 3);
+        assert_eq!(builder.count_text(), 3);
+        assert_eq!(builder.len(), 8);
+    }
+
+    #[test]
+    fn builder_invert() {
+        let mut builder = Builder::new_force_color();
+        builder.invert().text("x").no_invert();
+        assert_eq!(builder.content[0], Entity::Ansi(Ansi::ReverseVideo));
+        assert_eq!(builder.content[2], Entity::Ansi(Ansi::ReverseOff));
+        assert_eq!(builder.as_string(), "\x1b[7mx\x1b[27m\x1b[0m");
+    }
+
+    #[test]
+    fn builder_print_locked() {
+        let mut buffer: Vec<u8> = Vec::new();
+        for (i, color) in [Ansi::FgRed, Ansi::FgGreen].iter().enumerate() {
+            let mut builder = Builder::new_force_color();
+            builder.ansi(*color).text(&i.to_string()).reset();
+            builder.print_into(&mut buffer).unwrap();
         }
+        assert_eq!(buffer, b"\x1b[31m0\x1b[0m\x1b[32m1\x1b[0m");
+
+        let stdout = std::io::stdout();
+        let mut lock = stdout.lock();
+        Builder::new().text("").print_locked(&mut lock).unwrap();
     }
 
     #[test]
-    fn builder_print() {
+    fn builder_boxed() {
+        let mut builder = Builder::new_force_color();
+        builder.boxed(&["ab", "\x1b[1mcdef\x1b[0m"], Ansi::FgRed);
+        assert_eq!(
+            utils::strip_ansi(&builder.as_string()),
+            "┌──────┐\n│ ab   │\n│ cdef │\n└──────┘\n"
+        );
+        assert!(builder
+            .as_string()
+            .starts_with("\x1b[31m┌──────┐\x1b[0m\n\x1b[31m│\x1b[0m"));
+
         let mut builder = Builder::new();
-        builder.force_color = true;
-        builder.text("Hello, ").bold().text("world!").reset();
-        // Redirect stdout for testing
-        let output = std::io::stdout();
-        let mut handle = output.lock();
-        builder.print();
-        assert_eq!(handle.write(b"Hello, \x1b[1mworld!\x1b[0m").is_ok(), true);
+        builder.boxed(&[], Ansi::FgRed);
+        assert_eq!(builder.plain_text(), "┌┐\n└┘\n");
     }
 
     #[test]
-    fn builder_println() {
+    fn builder_blink_off() {
+        let mut builder = Builder::new_force_color();
+        builder
+            .blink_slow()
+            .text("a")
+            .blink_off()
+            .blink_rapid()
+            .text("b")
+            .blink_off();
+        assert_eq!(builder.content[2], Entity::Ansi(Ansi::BlinkOff));
+        assert_eq!(builder.as_string(), "\x1b[5ma\x1b[25;6mb\x1b[25m\x1b[0m");
+    }
+
+    #[test]
+    fn builder_section() {
         let mut builder = Builder::new();
-        builder.force_color = true;
-        builder.text("Hello, ").bold().text("world!").reset();
-        // Redirect stdout for testing
-        let output = std::io::stdout();
-        let mut handle = output.lock();
-        builder.println();
-        assert_eq!(handle.write(b"Hello, \x1b[1mworld!\x1b[0m\n").is_ok(), true);
+        builder.text("a").section(|b| {
+            b.fg_red().text("b").reset();
+        });
+        assert_eq!(
+            builder.content,
+            vec![
+                Entity::Text("a".to_string()),
+                Entity::Ansi(Ansi::FgRed),
+                Entity::Text("b".to_string()),
+                Entity::Ansi(Ansi::Reset),
+            ]
+        );
     }
 
     #[test]
-    fn builder_to_string() {
+    fn builder_reserve() {
+        let mut builder = Builder::new_force_color();
+        builder.reserve(3).fg_red().text("a").reset();
+        assert!(builder.content.capacity() >= 3);
+        assert_eq!(builder.as_string(), "\x1b[31ma\x1b[0m");
+    }
+
+    #[test]
+    fn builder_color_enabled() {
         let mut builder = Builder::new();
+        builder.no_color = false;
+        builder.force_color = false;
+        assert!(builder.color_enabled());
+        builder.no_color = true;
+        assert!(!builder.color_enabled());
         builder.force_color = true;
-        builder.text("Hello, ").bold().text("world!").reset();
-        assert_eq!(builder.as_string(), "Hello, \u{1b}[1mworld!\u{1b}[0m");
+        assert!(builder.color_enabled());
     }
 }
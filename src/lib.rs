@@ -3,4 +3,5 @@ pub mod macros;
 
 pub mod ansi;
 pub mod builder;
+pub mod style;
 pub mod utils;
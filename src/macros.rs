@@ -1,4 +1,5 @@
 /// Macros for printing colored text.
+/// Accepts either a single text or format arguments like `print!`.
 ///
 /// # Example
 ///
@@ -6,16 +7,22 @@
 /// use rcolors::*;
 ///
 /// color_print!(Ansi::FgRed, "This is red text");
-/// ``````
+/// color_print!(Ansi::FgRed, "This is {} text", "red");
+/// ```
 #[macro_export]
 macro_rules! color_print {
-    ($color:expr, $text:expr) => {{
+    ($color:expr, $text:expr $(,)?) => {{
         use $crate::ansi::Ansi;
         print!("{}{}{}", $color, $text, Ansi::Reset);
     }};
+    ($color:expr, $fmt:expr, $($arg:tt)*) => {{
+        use $crate::ansi::Ansi;
+        print!("{}{}{}", $color, format_args!($fmt, $($arg)*), Ansi::Reset);
+    }};
 }
 
 /// Print colored text with a newline.
+/// Accepts either a single text or format arguments like `println!`.
 ///
 /// # Example
 ///
@@ -23,16 +30,22 @@ macro_rules! color_print {
 /// use rcolors::*;
 ///
 /// color_println!(Ansi::FgRed, "This is red text");
+/// color_println!(Ansi::FgRed, "x = {}", 1);
 /// ```
 #[macro_export]
 macro_rules! color_println {
-    ($color:expr, $text:expr) => {{
+    ($color:expr, $text:expr $(,)?) => {{
         use $crate::ansi::Ansi;
         println!("{}{}{}", $color, $text, Ansi::Reset);
     }};
+    ($color:expr, $fmt:expr, $($arg:tt)*) => {{
+        use $crate::ansi::Ansi;
+        println!("{}{}{}", $color, format_args!($fmt, $($arg)*), Ansi::Reset);
+    }};
 }
 
 /// Return colored text as a string.
+/// Accepts either a single text or format arguments like `format!`.
 ///
 /// # Example
 ///
@@ -41,13 +54,20 @@ macro_rules! color_println {
 ///
 /// let s = color_sprint!(Ansi::FgRed, "This is red text");
 /// assert_eq!(s, "\u{1b}[31mThis is red text\u{1b}[0m");
+///
+/// let s = color_sprint!(Ansi::FgGreen, "n={}", 5);
+/// assert_eq!(s, "\u{1b}[32mn=5\u{1b}[0m");
 /// ```
 #[macro_export]
 macro_rules! color_sprint {
-    ($color:expr, $text:expr) => {{
+    ($color:expr, $text:expr $(,)?) => {{
         use $crate::ansi::Ansi;
         format!("{}{}{}", $color, $text, Ansi::Reset)
     }};
+    ($color:expr, $fmt:expr, $($arg:tt)*) => {{
+        use $crate::ansi::Ansi;
+        format!("{}{}{}", $color, format_args!($fmt, $($arg)*), Ansi::Reset)
+    }};
 }
 
 /// Print black text without a newline.
@@ -878,6 +898,330 @@ macro_rules! hi_white {
     }};
 }
 
+/// Print bold text to the console.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// print_bold!("This is bold text");
+/// // This will print "\u{1b}[1mThis is bold text\u{1b}[0m" to the console.
+/// ```
+#[macro_export]
+macro_rules! print_bold {
+    ($text:expr) => {{
+        color_print!(Ansi::Bold, $text);
+    }};
+}
+
+/// Print bold text to the console with a newline.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// println_bold!("This is bold text");
+/// // This will print "\u{1b}[1mThis is bold text\u{1b}[0m\n" to the console.
+/// ```
+#[macro_export]
+macro_rules! println_bold {
+    ($text:expr) => {{
+        color_println!(Ansi::Bold, $text);
+    }};
+}
+
+/// Return bold text as a string.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// let s = bold!("This is bold text");
+/// assert_eq!(s, "\u{1b}[1mThis is bold text\u{1b}[0m");
+/// ```
+#[macro_export]
+macro_rules! bold {
+    ($text:expr) => {{
+        let s = color_sprint!(Ansi::Bold, $text);
+        s
+    }};
+}
+
+/// Print italic text to the console.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// print_italic!("This is italic text");
+/// // This will print "\u{1b}[3mThis is italic text\u{1b}[0m" to the console.
+/// ```
+#[macro_export]
+macro_rules! print_italic {
+    ($text:expr) => {{
+        color_print!(Ansi::Italic, $text);
+    }};
+}
+
+/// Print italic text to the console with a newline.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// println_italic!("This is italic text");
+/// // This will print "\u{1b}[3mThis is italic text\u{1b}[0m\n" to the console.
+/// ```
+#[macro_export]
+macro_rules! println_italic {
+    ($text:expr) => {{
+        color_println!(Ansi::Italic, $text);
+    }};
+}
+
+/// Return italic text as a string.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// let s = italic!("This is italic text");
+/// assert_eq!(s, "\u{1b}[3mThis is italic text\u{1b}[0m");
+/// ```
+#[macro_export]
+macro_rules! italic {
+    ($text:expr) => {{
+        let s = color_sprint!(Ansi::Italic, $text);
+        s
+    }};
+}
+
+/// Print underlined text to the console.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// print_underline!("This is underlined text");
+/// // This will print "\u{1b}[4mThis is underlined text\u{1b}[0m" to the console.
+/// ```
+#[macro_export]
+macro_rules! print_underline {
+    ($text:expr) => {{
+        color_print!(Ansi::Underline, $text);
+    }};
+}
+
+/// Print underlined text to the console with a newline.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// println_underline!("This is underlined text");
+/// // This will print "\u{1b}[4mThis is underlined text\u{1b}[0m\n" to the console.
+/// ```
+#[macro_export]
+macro_rules! println_underline {
+    ($text:expr) => {{
+        color_println!(Ansi::Underline, $text);
+    }};
+}
+
+/// Return underlined text as a string.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// let s = underline!("This is underlined text");
+/// assert_eq!(s, "\u{1b}[4mThis is underlined text\u{1b}[0m");
+/// ```
+#[macro_export]
+macro_rules! underline {
+    ($text:expr) => {{
+        let s = color_sprint!(Ansi::Underline, $text);
+        s
+    }};
+}
+
+/// Print faint text to the console.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// print_faint!("This is faint text");
+/// // This will print "\u{1b}[2mThis is faint text\u{1b}[0m" to the console.
+/// ```
+#[macro_export]
+macro_rules! print_faint {
+    ($text:expr) => {{
+        color_print!(Ansi::Faint, $text);
+    }};
+}
+
+/// Print faint text to the console with a newline.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// println_faint!("This is faint text");
+/// // This will print "\u{1b}[2mThis is faint text\u{1b}[0m\n" to the console.
+/// ```
+#[macro_export]
+macro_rules! println_faint {
+    ($text:expr) => {{
+        color_println!(Ansi::Faint, $text);
+    }};
+}
+
+/// Return faint text as a string.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// let s = faint!("This is faint text");
+/// assert_eq!(s, "\u{1b}[2mThis is faint text\u{1b}[0m");
+/// ```
+#[macro_export]
+macro_rules! faint {
+    ($text:expr) => {{
+        let s = color_sprint!(Ansi::Faint, $text);
+        s
+    }};
+}
+
+/// Print crossed out text to the console.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// print_crossed_out!("This is crossed out text");
+/// // This will print "\u{1b}[9mThis is crossed out text\u{1b}[0m" to the console.
+/// ```
+#[macro_export]
+macro_rules! print_crossed_out {
+    ($text:expr) => {{
+        color_print!(Ansi::CrossedOut, $text);
+    }};
+}
+
+/// Print crossed out text to the console with a newline.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// println_crossed_out!("This is crossed out text");
+/// // This will print "\u{1b}[9mThis is crossed out text\u{1b}[0m\n" to the console.
+/// ```
+#[macro_export]
+macro_rules! println_crossed_out {
+    ($text:expr) => {{
+        color_println!(Ansi::CrossedOut, $text);
+    }};
+}
+
+/// Return crossed out text as a string.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// let s = crossed_out!("This is crossed out text");
+/// assert_eq!(s, "\u{1b}[9mThis is crossed out text\u{1b}[0m");
+/// ```
+#[macro_export]
+macro_rules! crossed_out {
+    ($text:expr) => {{
+        let s = color_sprint!(Ansi::CrossedOut, $text);
+        s
+    }};
+}
+
+/// Return text with multiple styles as a string.
+/// All codes are emitted before the text, followed by a single reset.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// let s = styled!([Ansi::Bold, Ansi::FgRed], "This is bold red text");
+/// assert_eq!(s, "\u{1b}[1m\u{1b}[31mThis is bold red text\u{1b}[0m");
+///
+/// let s = styled!([Ansi::Bold, Ansi::FgRed], "n={}", 5);
+/// assert_eq!(s, "\u{1b}[1m\u{1b}[31mn=5\u{1b}[0m");
+/// ```
+#[macro_export]
+macro_rules! styled {
+    ([$($style:expr),* $(,)?], $text:expr $(,)?) => {{
+        use $crate::ansi::Ansi;
+        let styles: &[Ansi] = &[$($style),*];
+        let codes: String = styles.iter().map(|style| style.to_string()).collect();
+        color_sprint!(codes, $text)
+    }};
+    ([$($style:expr),* $(,)?], $fmt:expr, $($arg:tt)*) => {{
+        use $crate::ansi::Ansi;
+        let styles: &[Ansi] = &[$($style),*];
+        let codes: String = styles.iter().map(|style| style.to_string()).collect();
+        color_sprint!(codes, $fmt, $($arg)*)
+    }};
+}
+
+/// Print text with multiple styles to the console.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// print_styled!([Ansi::Bold, Ansi::FgRed], "This is bold red text");
+/// // This will print "\u{1b}[1m\u{1b}[31mThis is bold red text\u{1b}[0m" to the console.
+/// ```
+#[macro_export]
+macro_rules! print_styled {
+    ($($arg:tt)*) => {{
+        print!("{}", styled!($($arg)*));
+    }};
+}
+
+/// Print text with multiple styles to the console with a newline.
+///
+/// # Example
+///
+/// ```
+/// use rcolors::*;
+///
+/// println_styled!([Ansi::Bold, Ansi::FgRed], "This is bold red text");
+/// // This will print "\u{1b}[1m\u{1b}[31mThis is bold red text\u{1b}[0m\n" to the console.
+/// ```
+#[macro_export]
+macro_rules! println_styled {
+    ($($arg:tt)*) => {{
+        println!("{}", styled!($($arg)*));
+    }};
+}
+
 #[cfg(test)]
 mod macro_tests {
     #[test]
@@ -895,4 +1239,46 @@ mod macro_tests {
         let s = color_sprint!(Ansi::FgRed, "This is red text");
         assert_eq!(s, "\u{1b}[31mThis is red text\u{1b}[0m");
     }
+
+    #[test]
+    fn macro_color_format_args() {
+        let n = 5;
+        assert_eq!(
+            color_sprint!(Ansi::FgGreen, "n={}", 5),
+            "\u{1b}[32mn=5\u{1b}[0m"
+        );
+        assert_eq!(
+            color_sprint!(Ansi::FgGreen, "n={n}, m={}", n + 1),
+            "\u{1b}[32mn=5, m=6\u{1b}[0m"
+        );
+        let text = String::from("text");
+        assert_eq!(color_sprint!(Ansi::FgRed, text), "\u{1b}[31mtext\u{1b}[0m");
+        color_print!(Ansi::FgRed, "x = {}", n);
+        color_println!(Ansi::FgRed, "x = {}", n);
+    }
+
+    #[test]
+    fn macro_styles() {
+        assert_eq!(bold!("x"), "\x1b[1mx\x1b[0m");
+        assert_eq!(italic!("x"), "\x1b[3mx\x1b[0m");
+        assert_eq!(underline!("x"), "\x1b[4mx\x1b[0m");
+        assert_eq!(faint!("x"), "\x1b[2mx\x1b[0m");
+        assert_eq!(crossed_out!("x"), "\x1b[9mx\x1b[0m");
+        print_bold!("x");
+        println_bold!("x");
+    }
+
+    #[test]
+    fn macro_styled() {
+        let s = styled!([Ansi::Bold, Ansi::FgRed], "x");
+        assert_eq!(s, "\x1b[1m\x1b[31mx\x1b[0m");
+        assert_eq!(s.matches("\x1b[0m").count(), 1);
+        assert_eq!(styled!([], "x"), "x\x1b[0m");
+        assert_eq!(
+            styled!([Ansi::Italic, Ansi::BgRgb(1, 2, 3)], "n={}", 5),
+            "\x1b[3m\x1b[48;2;1;2;3mn=5\x1b[0m"
+        );
+        print_styled!([Ansi::Bold, Ansi::FgRed], "x");
+        println_styled!([Ansi::Bold, Ansi::FgRed], "x");
+    }
 }
@@ -0,0 +1,71 @@
+use crate::ansi::Ansi;
+
+/// A reusable set of ANSI codes made of a foreground color,
+/// a background color and attributes.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::ansi::Ansi;
+/// use rcolors::builder::Builder;
+/// use rcolors::style::Style;
+///
+/// let error = Style {
+///     fg: Some(Ansi::FgRed),
+///     bg: None,
+///     attrs: vec![Ansi::Bold],
+/// };
+/// let mut builder = Builder::new();
+/// builder.style(&error, "error").text(": ").style(&error, "again");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    pub fg: Option<Ansi>,
+    pub bg: Option<Ansi>,
+    pub attrs: Vec<Ansi>,
+}
+
+impl Style {
+    /// Returns the ANSI codes of the style.
+    /// The attributes come first, followed by the foreground and background colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rcolors::ansi::Ansi;
+    /// use rcolors::style::Style;
+    ///
+    /// let style = Style {
+    ///     fg: Some(Ansi::FgRed),
+    ///     bg: Some(Ansi::BgBlack),
+    ///     attrs: vec![Ansi::Bold],
+    /// };
+    /// assert_eq!(style.codes(), vec![Ansi::Bold, Ansi::FgRed, Ansi::BgBlack]);
+    /// ```
+    pub fn codes(&self) -> Vec<Ansi> {
+        let mut codes = self.attrs.clone();
+        codes.extend(self.fg);
+        codes.extend(self.bg);
+        codes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_codes() {
+        assert!(Style::default().codes().is_empty());
+
+        let style = Style {
+            fg: None,
+            bg: Some(Ansi::BgBlue),
+            attrs: vec![Ansi::Italic, Ansi::Underline],
+        };
+        assert_eq!(
+            style.codes(),
+            vec![Ansi::Italic, Ansi::Underline, Ansi::BgBlue]
+        );
+    }
+}
@@ -1,3 +1,551 @@
+use crate::ansi::{self, Ansi, Segment};
+
+/// Represents the color capability level used when rendering.
+///
+/// Levels are ordered from no color support to full 24-bit color support.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils::ColorLevel;
+///
+/// assert!(ColorLevel::TrueColor > ColorLevel::Ansi16);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/// Represents the output stream used for color detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
 pub fn no_color() -> bool {
-    std::env::var("NO_COLOR").is_ok() || !atty::is(atty::Stream::Stdout)
+    no_color_for(Stream::Stdout)
+}
+
+/// Returns whether colors should be disabled for the given stream.
+///
+/// The environment is checked in the following order:
+/// 1. `FORCE_COLOR` set to a non-empty value other than `0` enables colors.
+/// 2. `NO_COLOR` set to any value disables colors.
+/// 3. `CLICOLOR_FORCE` set to a non-empty value other than `0` enables colors.
+/// 4. `CLICOLOR` set to `0` disables colors.
+/// 5. Colors are disabled if the stream is not a TTY.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils::{self, Stream};
+///
+/// let no_color = utils::no_color_for(Stream::Stderr);
+/// ```
+pub fn no_color_for(stream: Stream) -> bool {
+    if enabled("FORCE_COLOR") {
+        return false;
+    }
+    if std::env::var("NO_COLOR").is_ok() {
+        return true;
+    }
+    if enabled("CLICOLOR_FORCE") {
+        return false;
+    }
+    matches!(std::env::var("CLICOLOR"), Ok(value) if value == "0") || !is_terminal(stream)
+}
+
+// Returns whether the stream is a TTY, using `std::io::IsTerminal`.
+#[cfg(feature = "std-tty")]
+fn is_terminal(stream: Stream) -> bool {
+    use std::io::IsTerminal;
+    match stream {
+        Stream::Stdout => std::io::stdout().is_terminal(),
+        Stream::Stderr => std::io::stderr().is_terminal(),
+    }
+}
+
+// Returns whether the stream is a TTY, using the `atty` fallback.
+#[cfg(all(not(feature = "std-tty"), feature = "atty"))]
+fn is_terminal(stream: Stream) -> bool {
+    match stream {
+        Stream::Stdout => atty::is(atty::Stream::Stdout),
+        Stream::Stderr => atty::is(atty::Stream::Stderr),
+    }
+}
+
+// Without TTY detection, streams are never treated as TTY.
+#[cfg(not(any(feature = "std-tty", feature = "atty")))]
+fn is_terminal(_stream: Stream) -> bool {
+    false
+}
+
+// Returns whether the variable is set to a non-empty value other than `0`.
+fn enabled(key: &str) -> bool {
+    matches!(std::env::var(key), Ok(value) if !value.is_empty() && value != "0")
+}
+
+/// Detects the color capabilities of the current terminal.
+/// This is the same as `color_level`.
+#[deprecated(note = "use `color_level` instead")]
+pub fn color_support() -> ColorLevel {
+    color_level()
+}
+
+/// Detects the color capabilities of the terminal behind the given stream.
+/// This is the same as `color_level_for`.
+#[deprecated(note = "use `color_level_for` instead")]
+pub fn color_support_for(stream: Stream) -> ColorLevel {
+    color_level_for(stream)
+}
+
+/// Returns whether the current terminal supports 24-bit colors.
+/// This is the case if `COLORTERM` is set to `truecolor` or `24bit`
+/// and colors are not disabled, see `no_color`.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::ansi::Ansi;
+/// use rcolors::utils;
+///
+/// let orange = if utils::supports_truecolor() {
+///     Ansi::FgRgb(255, 165, 0)
+/// } else {
+///     Ansi::FgYellow
+/// };
+/// ```
+pub fn supports_truecolor() -> bool {
+    color_level() == ColorLevel::TrueColor
+}
+
+/// Detects the color capability level of the current terminal.
+/// `COLORTERM` set to `truecolor` or `24bit` means 24-bit colors and
+/// a `TERM` ending with `256color` means 256 colors.
+/// If colors are disabled, see `no_color`, `ColorLevel::None` is returned.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils::{self, ColorLevel};
+///
+/// if utils::color_level() == ColorLevel::TrueColor {
+///     println!("24-bit colors are supported");
+/// }
+/// ```
+pub fn color_level() -> ColorLevel {
+    color_level_for(Stream::Stdout)
+}
+
+/// Detects the color capability level of the terminal behind the given stream.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils::{self, Stream};
+///
+/// let level = utils::color_level_for(Stream::Stderr);
+/// ```
+pub fn color_level_for(stream: Stream) -> ColorLevel {
+    if no_color_for(stream) {
+        return ColorLevel::None;
+    }
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorLevel::TrueColor;
+    }
+    if std::env::var("TERM")
+        .unwrap_or_default()
+        .ends_with("256color")
+    {
+        return ColorLevel::Ansi256;
+    }
+    ColorLevel::Ansi16
+}
+
+/// Removes ANSI SGR escape sequences (`\x1b[...m`) from a string.
+/// Other text is left untouched.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils;
+///
+/// assert_eq!(utils::strip_ansi("\x1b[1;31mred\x1b[0m text"), "red text");
+/// ```
+pub fn strip_ansi(input: &str) -> String {
+    ansi::tokenize(input)
+        .filter_map(|segment| match segment {
+            Segment::Sgr(_) => None,
+            Segment::Text(text) | Segment::Other(text) => Some(text),
+        })
+        .collect()
+}
+
+/// Returns the number of visible characters of a string.
+/// ANSI escape sequences, SGR or others like links and cursor movements, are not counted.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils;
+///
+/// assert_eq!(utils::visible_len("\x1b[31mred\x1b[0m"), 3);
+/// ```
+pub fn visible_len(input: &str) -> usize {
+    ansi::tokenize(input)
+        .map(|segment| match segment {
+            Segment::Text(text) => text.chars().count(),
+            Segment::Sgr(_) | Segment::Other(_) => 0,
+        })
+        .sum()
+}
+
+/// Splits a string with ANSI SGR escape sequences into runs of `(foreground, text)`.
+/// A reset or `FgDefault` clears the foreground, background and style codes are ignored.
+/// Adjacent runs with the same foreground are merged and empty runs are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::ansi::Ansi;
+/// use rcolors::utils;
+///
+/// assert_eq!(utils::parse_spans("a\x1b[1;31mb\x1b[0mc"), vec![
+///     (None, "a".to_string()),
+///     (Some(Ansi::FgRed), "b".to_string()),
+///     (None, "c".to_string()),
+/// ]);
+/// ```
+pub fn parse_spans(input: &str) -> Vec<(Option<Ansi>, String)> {
+    let mut spans: Vec<(Option<Ansi>, String)> = Vec::new();
+    let mut fg = None;
+    let mut push = |fg: Option<Ansi>, text: &str| {
+        if text.is_empty() {
+            return;
+        }
+        match spans.last_mut() {
+            Some((last, last_text)) if *last == fg => last_text.push_str(text),
+            _ => spans.push((fg, text.to_string())),
+        }
+    };
+    for segment in ansi::tokenize(input) {
+        match segment {
+            Segment::Sgr(params) => {
+                for code in ansi::parse_params(params) {
+                    match code {
+                        Ansi::Reset | Ansi::FgDefault => fg = None,
+                        code if code.is_foreground() => fg = Some(code),
+                        _ => {}
+                    }
+                }
+            }
+            Segment::Text(text) | Segment::Other(text) => push(fg, text),
+        }
+    }
+    spans
+}
+
+/// Converts a RGB color to the closest basic 16 foreground color.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::ansi::Ansi;
+/// use rcolors::utils;
+///
+/// assert_eq!(utils::rgb_to_basic16(255, 0, 0), Ansi::FgRed);
+/// ```
+pub fn rgb_to_basic16(r: u8, g: u8, b: u8) -> Ansi {
+    Ansi::FgRgb(r, g, b).to_basic()
+}
+
+/// Interpolates linearly between two RGB colors.
+/// `t` is clamped to `0.0..=1.0` and the channels are rounded.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils;
+///
+/// assert_eq!(utils::lerp_rgb((0, 0, 0), (255, 255, 255), 0.5), (128, 128, 128));
+/// ```
+pub fn lerp_rgb(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+}
+
+/// Enables ANSI escape code processing on the Windows console.
+/// Sets `ENABLE_VIRTUAL_TERMINAL_PROCESSING` for stdout and stderr and
+/// returns whether it succeeded for at least one of them.
+/// On other platforms this does nothing and returns `true`.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils;
+///
+/// let enabled = utils::enable_windows_ansi();
+/// ```
+#[cfg(windows)]
+pub fn enable_windows_ansi() -> bool {
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleMode, ENABLE_VIRTUAL_TERMINAL_PROCESSING,
+        STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
+    };
+
+    let mut enabled = false;
+    for std_handle in [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE] {
+        // SAFETY: The handle comes from `GetStdHandle` and `mode` is a valid pointer.
+        unsafe {
+            let handle = GetStdHandle(std_handle);
+            let mut mode = 0;
+            if GetConsoleMode(handle, &mut mode) != 0
+                && SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0
+            {
+                enabled = true;
+            }
+        }
+    }
+    enabled
+}
+
+/// Enables ANSI escape code processing on the Windows console.
+/// On other platforms this does nothing and returns `true`.
+///
+/// # Examples
+///
+/// ```
+/// use rcolors::utils;
+///
+/// let enabled = utils::enable_windows_ansi();
+/// ```
+#[cfg(not(windows))]
+pub fn enable_windows_ansi() -> bool {
+    true
+}
+
+// Enables ANSI escape code processing once before the first print.
+pub(crate) fn enable_windows_ansi_once() {
+    static ONCE: std::sync::Once = std::sync::Once::new();
+    ONCE.call_once(|| {
+        enable_windows_ansi();
+    });
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::env;
+    use std::sync::{Mutex, MutexGuard};
+
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    // Sets environment variables for the lifetime of the guard.
+    // Tests touching the environment are serialized through a global lock
+    // and the previous values are restored on drop.
+    pub(crate) struct EnvGuard {
+        saved: Vec<(String, Option<String>)>,
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl EnvGuard {
+        pub(crate) fn new(vars: &[(&str, Option<&str>)]) -> Self {
+            let lock = ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let mut saved = Vec::new();
+            for (key, value) in vars {
+                saved.push((key.to_string(), env::var(key).ok()));
+                match value {
+                    Some(value) => env::set_var(key, value),
+                    None => env::remove_var(key),
+                }
+            }
+            Self { saved, _lock: lock }
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for (key, value) in self.saved.iter().rev() {
+                match value {
+                    Some(value) => env::set_var(key, value),
+                    None => env::remove_var(key),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::EnvGuard;
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        let s = color_sprint!(crate::ansi::Ansi::FgRed, "This is red text");
+        assert_eq!(strip_ansi(&s), "This is red text");
+        assert_eq!(strip_ansi("\x1b[1;31mx\x1b[0my\x1b[48;2;1;2;3mz"), "xyz");
+        assert_eq!(strip_ansi("plain text"), "plain text");
+        assert_eq!(strip_ansi(""), "");
+    }
+
+    #[test]
+    fn test_strip_ansi_keeps_other_sequences() {
+        assert_eq!(strip_ansi("\x1b[2Jclear"), "\x1b[2Jclear");
+        assert_eq!(strip_ansi("end\x1b["), "end\x1b[");
+    }
+
+    #[test]
+    fn test_visible_len() {
+        let s = color_sprint!(crate::ansi::Ansi::FgRed, "This is red text");
+        assert_eq!(visible_len(&s), "This is red text".len());
+        assert_eq!(visible_len("\x1b[1;31mäöü\x1b[0m"), 3);
+        assert_eq!(
+            visible_len("\x1b]8;;https://example.com\x1b\\x\x1b]8;;\x1b\\"),
+            1
+        );
+        assert_eq!(visible_len("\x1b[2Aab"), 2);
+        assert_eq!(visible_len(""), 0);
+    }
+
+    #[test]
+    fn test_force_color() {
+        let _env = EnvGuard::new(&[("FORCE_COLOR", Some("1")), ("NO_COLOR", Some("1"))]);
+        assert!(!no_color());
+        assert!(!no_color_for(Stream::Stderr));
+    }
+
+    #[test]
+    fn test_force_color_disabled_values() {
+        for value in [Some("0"), Some(""), None] {
+            let _env = EnvGuard::new(&[("FORCE_COLOR", value), ("NO_COLOR", Some("1"))]);
+            assert!(no_color());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std-tty")]
+    fn test_is_terminal() {
+        use std::io::IsTerminal;
+        assert_eq!(is_terminal(Stream::Stdout), std::io::stdout().is_terminal());
+        assert_eq!(is_terminal(Stream::Stderr), std::io::stderr().is_terminal());
+    }
+
+    #[test]
+    fn test_clicolor() {
+        let cases = [
+            (None, None, Some("1"), None, false),
+            (None, None, Some("1"), Some("0"), false),
+            (None, None, Some("0"), Some("0"), true),
+            (None, None, None, Some("0"), true),
+            (None, Some("1"), Some("1"), None, true),
+            (Some("1"), None, None, Some("0"), false),
+        ];
+        for (force_color, no_color_value, clicolor_force, clicolor, expected) in cases {
+            let _env = EnvGuard::new(&[
+                ("FORCE_COLOR", force_color),
+                ("NO_COLOR", no_color_value),
+                ("CLICOLOR_FORCE", clicolor_force),
+                ("CLICOLOR", clicolor),
+            ]);
+            assert_eq!(no_color(), expected);
+        }
+    }
+
+    #[test]
+    fn test_rgb_to_basic16() {
+        assert_eq!(rgb_to_basic16(255, 0, 0), Ansi::FgRed);
+        assert_eq!(rgb_to_basic16(64, 64, 64), Ansi::FgHiBlack);
+        assert_eq!(rgb_to_basic16(0, 0, 0), Ansi::FgBlack);
+    }
+
+    #[test]
+    fn test_lerp_rgb() {
+        let (a, b) = ((0, 100, 255), (255, 200, 0));
+        assert_eq!(lerp_rgb(a, b, 0.0), a);
+        assert_eq!(lerp_rgb(a, b, 1.0), b);
+        assert_eq!(lerp_rgb(a, b, 0.5), (128, 150, 128));
+        assert_eq!(lerp_rgb(a, b, -1.0), a);
+        assert_eq!(lerp_rgb(a, b, 2.0), b);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_color_support() {
+        let forced = Some("1");
+        let cases = [
+            (None, Some("1"), Some("truecolor"), None, ColorLevel::None),
+            (forced, None, Some("truecolor"), None, ColorLevel::TrueColor),
+            (forced, None, Some("24bit"), None, ColorLevel::TrueColor),
+            (
+                forced,
+                None,
+                None,
+                Some("xterm-256color"),
+                ColorLevel::Ansi256,
+            ),
+            (forced, None, Some("yes"), Some("xterm"), ColorLevel::Ansi16),
+            (forced, None, None, None, ColorLevel::Ansi16),
+        ];
+        for (force_color, no_color, colorterm, term, expected) in cases {
+            let _env = EnvGuard::new(&[
+                ("FORCE_COLOR", force_color),
+                ("NO_COLOR", no_color),
+                ("COLORTERM", colorterm),
+                ("TERM", term),
+            ]);
+            assert_eq!(color_support(), expected);
+            assert_eq!(color_level(), expected);
+        }
+    }
+
+    #[test]
+    fn test_supports_truecolor() {
+        let cases = [
+            (Some("1"), None, Some("truecolor"), true),
+            (Some("1"), None, Some("24bit"), true),
+            (Some("1"), None, Some("256color"), false),
+            (Some("1"), None, None, false),
+            (None, Some("1"), Some("truecolor"), false),
+        ];
+        for (force_color, no_color_value, colorterm, expected) in cases {
+            let _env = EnvGuard::new(&[
+                ("FORCE_COLOR", force_color),
+                ("NO_COLOR", no_color_value),
+                ("COLORTERM", colorterm),
+            ]);
+            assert_eq!(supports_truecolor(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_spans() {
+        let input = format!(
+            "{} {}\x1b[44m!",
+            color_sprint!(Ansi::FgRed, "red"),
+            color_sprint!(Ansi::Fg256(42), "fg{}", 256)
+        );
+        assert_eq!(
+            parse_spans(&input),
+            vec![
+                (Some(Ansi::FgRed), "red".to_string()),
+                (None, " ".to_string()),
+                (Some(Ansi::Fg256(42)), "fg256".to_string()),
+                (None, "!".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_spans("\x1b[31ma\x1b[39mb\x1b[Kc"),
+            vec![
+                (Some(Ansi::FgRed), "a".to_string()),
+                (None, "b\x1b[Kc".to_string()),
+            ]
+        );
+        assert!(parse_spans("").is_empty());
+    }
 }
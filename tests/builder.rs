@@ -10,6 +10,15 @@ mod builder_tests {
         assert_eq!(builder.as_string(), "\x1b[40mtext\x1b[0m");
     }
 
+    #[test]
+    fn builder_to_string_trait() {
+        let mut builder = Builder::new();
+        builder.fg_red().text("text").reset().force_color();
+
+        assert_eq!(ToString::to_string(&builder), builder.as_string());
+        assert_eq!(builder.to_string(), "\x1b[31mtext\x1b[0m");
+    }
+
     #[test]
     fn builder_print() {
         Builder::new()
@@ -9,10 +9,7 @@ mod macro_tests {
         let output = std::io::stdout();
         let mut handle = output.lock();
         color_print!(Ansi::FgRed, "This is red text");
-        assert_eq!(
-            handle.write(b"\x1b[31mThis is red text\x1b[0m").is_ok(),
-            true
-        );
+        assert!(handle.write(b"\x1b[31mThis is red text\x1b[0m").is_ok());
     }
 
     #[test]
@@ -21,10 +18,7 @@ mod macro_tests {
         let output = std::io::stdout();
         let mut handle = output.lock();
         color_println!(Ansi::FgRed, "This is red text");
-        assert_eq!(
-            handle.write(b"\x1b[31mThis is red text\x1b[0m\n").is_ok(),
-            true
-        );
+        assert!(handle.write(b"\x1b[31mThis is red text\x1b[0m\n").is_ok());
     }
 
     #[test]
@@ -32,4 +26,13 @@ mod macro_tests {
         let s = color_sprint!(Ansi::FgRed, "This is red text");
         assert_eq!(s, "\u{1b}[31mThis is red text\u{1b}[0m");
     }
+
+    #[test]
+    fn test_color_sprint_single_literal() {
+        let s = color_sprint!(Ansi::FgRed, 5);
+        assert_eq!(s, "\u{1b}[31m5\u{1b}[0m");
+
+        let s = color_sprint!(Ansi::FgRed, "{}");
+        assert_eq!(s, "\u{1b}[31m{}\u{1b}[0m");
+    }
 }
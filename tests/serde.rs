@@ -0,0 +1,44 @@
+#![cfg(feature = "serde")]
+
+#[cfg(test)]
+mod serde_tests {
+    use rcolors::ansi::Ansi;
+    use rcolors::builder::{Builder, Entity};
+
+    #[test]
+    fn ansi_round_trip() {
+        let json = serde_json::to_string(&Ansi::FgRed).unwrap();
+        assert_eq!(json, "\"FgRed\"");
+        assert_eq!(serde_json::from_str::<Ansi>(&json).unwrap(), Ansi::FgRed);
+
+        let json = serde_json::to_string(&Ansi::FgRgb(255, 128, 0)).unwrap();
+        assert_eq!(json, "{\"FgRgb\":[255,128,0]}");
+        assert_eq!(
+            serde_json::from_str::<Ansi>(&json).unwrap(),
+            Ansi::FgRgb(255, 128, 0)
+        );
+    }
+
+    #[test]
+    fn entity_round_trip() {
+        let entity = Entity::Text("text".to_string());
+        let json = serde_json::to_string(&entity).unwrap();
+        assert_eq!(json, "{\"Text\":\"text\"}");
+        assert_eq!(serde_json::from_str::<Entity>(&json).unwrap(), entity);
+    }
+
+    #[test]
+    fn builder_round_trip() {
+        let mut builder = Builder::new();
+        builder.bold().fg_red().text("text").reset();
+        let json = serde_json::to_string(&builder).unwrap();
+        assert_eq!(
+            json,
+            "[{\"Ansi\":\"Bold\"},{\"Ansi\":\"FgRed\"},{\"Text\":\"text\"},{\"Ansi\":\"Reset\"}]"
+        );
+
+        let mut restored: Builder = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.content_raw(), builder.content_raw());
+        assert_eq!(restored.force_color().as_string(), "\x1b[1;31mtext\x1b[0m");
+    }
+}